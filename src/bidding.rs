@@ -0,0 +1,489 @@
+//! This module contains the bidding (Reizen) as a state machine, see [Bidding].
+//!
+//! Mittelhand says values to Vorhand, who holds ("ja") or passes. The one left bids with
+//! Hinterhand, who says values in the same way. If both Mittelhand and Hinterhand pass without
+//! a bid, Vorhand may still bid 18 or pass, then nobody plays.
+
+use std::fmt::{self, Display};
+
+use crate::error::BiddingError;
+use crate::seat::{PlayerId, Seat};
+use crate::value::{GRAND_BASE_VALUE, NULL_VALUES, SUIT_BASE_VALUES};
+
+/// Levels added to the matadors: game, hand, Schneider, Schneider announced, Schwarz,
+/// Schwarz announced, ouvert.
+const MAX_LEVELS: u16 = 7;
+
+/// With or without 11 in a suit game, 4 in Grand.
+const MAX_MATADORS_SUIT: u16 = 11;
+const MAX_MATADORS_GRAND: u16 = 4;
+
+/// The value of a game: base value times (matadors + levels), or a Null value.
+const fn is_game_value(value: u16) -> bool {
+    const fn has_multiplier(value: u16, base: u16, max_matadors: u16) -> bool {
+        // at least with or without 1, game
+        value.is_multiple_of(base) && value / base >= 2 && value / base <= max_matadors + MAX_LEVELS
+    }
+    let mut i = 0;
+    while i < SUIT_BASE_VALUES.len() {
+        if has_multiplier(value, SUIT_BASE_VALUES[i], MAX_MATADORS_SUIT) || value == NULL_VALUES[i]
+        {
+            return true;
+        }
+        i += 1;
+    }
+    has_multiplier(value, GRAND_BASE_VALUE, MAX_MATADORS_GRAND)
+}
+
+/// Upper bound of the game values: Grand with 4 and all levels.
+const LADDER_LIMIT: u16 = GRAND_BASE_VALUE * (MAX_MATADORS_GRAND + MAX_LEVELS);
+
+const LADDER_LENGTH: usize = {
+    let mut count = 0;
+    let mut value = 0;
+    while value <= LADDER_LIMIT {
+        if is_game_value(value) {
+            count += 1;
+        }
+        value += 1;
+    }
+    count
+};
+
+/// All game values in ascending order.
+const LADDER: [BidValue; LADDER_LENGTH] = {
+    let mut ladder = [BidValue(0); LADDER_LENGTH];
+    let mut i = 0;
+    let mut value = 0;
+    while value <= LADDER_LIMIT {
+        if is_game_value(value) {
+            ladder[i] = BidValue(value);
+            i += 1;
+        }
+        value += 1;
+    }
+    ladder
+};
+
+/// A value which can be bid: the value of a game which can be played, e.g. 18, 20, 22, 23, ... 264.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "u16", into = "u16")
+)]
+pub struct BidValue(u16);
+
+impl BidValue {
+    /// All values in ascending order, generated from the rules of the game value.
+    pub const ALL: &'static [BidValue] = &LADDER;
+    pub const MIN: BidValue = LADDER[0];
+    pub const MAX: BidValue = LADDER[LADDER_LENGTH - 1];
+
+    /// The bid value, None if no game has this value.
+    pub fn new(value: u16) -> Option<BidValue> {
+        is_game_value(value).then_some(BidValue(value))
+    }
+
+    /// The highest bid not above the computed game value, e.g. when bidding up to the value
+    /// of a hand. None if the value is below 18.
+    pub fn at_most(value: u16) -> Option<BidValue> {
+        BidValue::ALL
+            .iter()
+            .rev()
+            .find(|bid| bid.0 <= value)
+            .copied()
+    }
+
+    pub fn value(&self) -> u16 {
+        self.0
+    }
+
+    /// The next higher value, None for [BidValue::MAX].
+    pub fn next(&self) -> Option<BidValue> {
+        BidValue::ALL.get(self.position() + 1).copied()
+    }
+
+    /// The next lower value, None for [BidValue::MIN].
+    pub fn previous(&self) -> Option<BidValue> {
+        BidValue::ALL.get(self.position().checked_sub(1)?).copied()
+    }
+
+    fn position(&self) -> usize {
+        // a BidValue is always on the ladder
+        BidValue::ALL.binary_search(self).unwrap_or_default()
+    }
+}
+
+impl TryFrom<u16> for BidValue {
+    type Error = BiddingError;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        BidValue::new(value).ok_or(BiddingError::InvalidValue { value })
+    }
+}
+
+impl From<BidValue> for u16 {
+    fn from(bid: BidValue) -> Self {
+        bid.0
+    }
+}
+
+impl Display for BidValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// What a player says during the bidding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BidAction {
+    /// The bidding player says a value.
+    Bid(BidValue),
+    /// The listening player holds the value ("ja").
+    Hold,
+    /// The player gives up ("passe").
+    Pass,
+}
+
+impl Display for BidAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BidAction::Bid(value) => write!(f, "{value}"),
+            BidAction::Hold => write!(f, "ja"),
+            BidAction::Pass => write!(f, "passe"),
+        }
+    }
+}
+
+/// The end of the bidding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BiddingResult {
+    /// The player plays, having bid or held the value.
+    Declarer { player: PlayerId, value: BidValue },
+    /// All players passed.
+    AllPassed,
+}
+
+/// The steps of the bidding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Phase {
+    /// The bidder says values to the listener, `awaiting_listener` after a bid.
+    Bidding {
+        bidder: Seat,
+        listener: Seat,
+        awaiting_listener: bool,
+    },
+    /// Mittelhand and Hinterhand passed without a bid, Vorhand may bid.
+    VorhandAlone,
+    Finished(BiddingResult),
+}
+
+/// The bidding of one game. Each player acts in turn with [Bidding::act], illegal actions are
+/// rejected and leave the bidding unchanged.
+/// ```
+/// use skat_engine::bidding::{BidAction, BidValue, Bidding, BiddingResult};
+/// use skat_engine::seat::{PlayerId, Seat};
+///
+/// let vorhand = PlayerId::ALL[0];
+/// let mut bidding = Bidding::new(vorhand);
+/// let mittelhand = Seat::Mittelhand.player(vorhand);
+/// bidding.act(mittelhand, BidAction::Bid(BidValue::MIN)).unwrap();
+/// bidding.act(vorhand, BidAction::Pass).unwrap();
+/// bidding.act(Seat::Hinterhand.player(vorhand), BidAction::Pass).unwrap();
+/// assert_eq!(
+///     bidding.result(),
+///     Some(BiddingResult::Declarer { player: mittelhand, value: BidValue::MIN })
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bidding {
+    vorhand: PlayerId,
+    phase: Phase,
+    /// The highest value bid so far.
+    value: Option<BidValue>,
+}
+
+impl Bidding {
+    pub fn new(vorhand: PlayerId) -> Bidding {
+        Bidding {
+            vorhand,
+            phase: Phase::Bidding {
+                bidder: Seat::Mittelhand,
+                listener: Seat::Vorhand,
+                awaiting_listener: false,
+            },
+            value: None,
+        }
+    }
+
+    /// The player to act, None when the bidding is finished.
+    pub fn turn(&self) -> Option<PlayerId> {
+        let seat = match self.phase {
+            Phase::Bidding {
+                awaiting_listener: true,
+                listener,
+                ..
+            } => listener,
+            Phase::Bidding { bidder, .. } => bidder,
+            Phase::VorhandAlone => Seat::Vorhand,
+            Phase::Finished(_) => return None,
+        };
+        Some(seat.player(self.vorhand))
+    }
+
+    /// The highest value bid so far, None before the first bid.
+    pub fn value(&self) -> Option<BidValue> {
+        self.value
+    }
+
+    /// The result when the bidding is finished.
+    pub fn result(&self) -> Option<BiddingResult> {
+        match self.phase {
+            Phase::Finished(result) => Some(result),
+            _ => None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.result().is_some()
+    }
+
+    /// The player acts.
+    /// # Errors
+    /// If it is not the player's turn, the action is not allowed now or the value is too low
+    /// or not the value of a game. The bidding is unchanged then.
+    pub fn act(&mut self, player: PlayerId, action: BidAction) -> Result<(), BiddingError> {
+        let Some(expected) = self.turn() else {
+            return Err(BiddingError::Finished);
+        };
+        if player != expected {
+            return Err(BiddingError::NotYourTurn {
+                expected,
+                found: player,
+            });
+        }
+        match (self.phase, action) {
+            (_, BidAction::Bid(_)) if self.is_listening() => {
+                Err(BiddingError::IllegalAction { action })
+            }
+            // after both others passed Vorhand can only play for 18
+            (Phase::VorhandAlone, BidAction::Bid(value)) if value != BidValue::MIN => {
+                Err(BiddingError::IllegalAction { action })
+            }
+            (_, BidAction::Bid(value)) => {
+                self.check_value(value)?;
+                self.value = Some(value);
+                self.phase = match self.phase {
+                    Phase::Bidding {
+                        bidder, listener, ..
+                    } => Phase::Bidding {
+                        bidder,
+                        listener,
+                        awaiting_listener: true,
+                    },
+                    _ => self.declarer(Seat::Vorhand),
+                };
+                Ok(())
+            }
+            (
+                Phase::Bidding {
+                    bidder,
+                    listener,
+                    awaiting_listener: true,
+                },
+                BidAction::Hold,
+            ) => {
+                self.phase = Phase::Bidding {
+                    bidder,
+                    listener,
+                    awaiting_listener: false,
+                };
+                Ok(())
+            }
+            (
+                Phase::Bidding {
+                    bidder,
+                    listener,
+                    awaiting_listener,
+                },
+                BidAction::Pass,
+            ) => {
+                let winner = if awaiting_listener { bidder } else { listener };
+                self.phase = match bidder {
+                    Seat::Mittelhand => Phase::Bidding {
+                        bidder: Seat::Hinterhand,
+                        listener: winner,
+                        awaiting_listener: false,
+                    },
+                    _ if self.value.is_some() => self.declarer(winner),
+                    _ => Phase::VorhandAlone,
+                };
+                Ok(())
+            }
+            (Phase::VorhandAlone, BidAction::Pass) => {
+                self.phase = Phase::Finished(BiddingResult::AllPassed);
+                Ok(())
+            }
+            _ => Err(BiddingError::IllegalAction { action }),
+        }
+    }
+
+    /// The action of a player who bids and holds up to the value, None to pass at once.
+    pub fn action_up_to(&self, max: Option<BidValue>) -> BidAction {
+        let Some(max) = max else {
+            return BidAction::Pass;
+        };
+        if self.is_listening() {
+            return match self.value {
+                Some(value) if value <= max => BidAction::Hold,
+                _ => BidAction::Pass,
+            };
+        }
+        match self.minimum() {
+            Some(next) if next <= max => BidAction::Bid(next),
+            _ => BidAction::Pass,
+        }
+    }
+
+    /// The lowest value to bid now, None if the highest value was bid.
+    pub fn minimum(&self) -> Option<BidValue> {
+        match self.value {
+            Some(value) => value.next(),
+            None => Some(BidValue::MIN),
+        }
+    }
+
+    fn is_listening(&self) -> bool {
+        matches!(
+            self.phase,
+            Phase::Bidding {
+                awaiting_listener: true,
+                ..
+            }
+        )
+    }
+
+    fn check_value(&self, value: BidValue) -> Result<(), BiddingError> {
+        match self.minimum() {
+            Some(minimum) if value >= minimum => Ok(()),
+            minimum => Err(BiddingError::BidTooLow {
+                value,
+                minimum: minimum.unwrap_or(BidValue::MAX),
+            }),
+        }
+    }
+
+    fn declarer(&self, seat: Seat) -> Phase {
+        Phase::Finished(BiddingResult::Declarer {
+            player: seat.player(self.vorhand),
+            value: self.value.unwrap_or(BidValue::MIN),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bid(value: u16) -> BidValue {
+        BidValue::new(value).unwrap()
+    }
+
+    #[test]
+    fn sequence() {
+        let vorhand = PlayerId::ALL[1];
+        let [vh, mh, hh] = Seat::ALL.map(|seat| seat.player(vorhand));
+        let mut bidding = Bidding::new(vorhand);
+        assert_eq!(bidding.turn(), Some(mh));
+        assert_eq!(
+            bidding.act(vh, BidAction::Pass),
+            Err(BiddingError::NotYourTurn {
+                expected: mh,
+                found: vh
+            })
+        );
+        bidding.act(mh, BidAction::Bid(bid(18))).unwrap();
+        assert_eq!(
+            bidding.act(vh, BidAction::Bid(bid(20))),
+            Err(BiddingError::IllegalAction {
+                action: BidAction::Bid(bid(20))
+            })
+        );
+        bidding.act(vh, BidAction::Hold).unwrap();
+        assert_eq!(
+            bidding.act(mh, BidAction::Bid(bid(18))),
+            Err(BiddingError::BidTooLow {
+                value: bid(18),
+                minimum: bid(20)
+            })
+        );
+        bidding.act(mh, BidAction::Pass).unwrap();
+        // Hinterhand bids to Vorhand who won against Mittelhand
+        assert_eq!((bidding.turn(), bidding.value()), (Some(hh), Some(bid(18))));
+        bidding.act(hh, BidAction::Bid(bid(20))).unwrap();
+        bidding.act(vh, BidAction::Pass).unwrap();
+        assert_eq!(
+            bidding.result(),
+            Some(BiddingResult::Declarer {
+                player: hh,
+                value: bid(20)
+            })
+        );
+        assert_eq!(
+            bidding.act(vh, BidAction::Pass),
+            Err(BiddingError::Finished)
+        );
+    }
+
+    #[test]
+    fn vorhand_alone() {
+        let vorhand = PlayerId::ALL[0];
+        let [vh, mh, hh] = Seat::ALL.map(|seat| seat.player(vorhand));
+        let mut bidding = Bidding::new(vorhand);
+        bidding.act(mh, BidAction::Pass).unwrap();
+        bidding.act(hh, BidAction::Pass).unwrap();
+        assert_eq!(bidding.turn(), Some(vh));
+        let mut all_passed = bidding.clone();
+        assert_eq!(
+            bidding.act(vh, BidAction::Bid(bid(30))),
+            Err(BiddingError::IllegalAction {
+                action: BidAction::Bid(bid(30))
+            })
+        );
+        bidding.act(vh, BidAction::Bid(bid(18))).unwrap();
+        assert_eq!(
+            bidding.result(),
+            Some(BiddingResult::Declarer {
+                player: vh,
+                value: bid(18)
+            })
+        );
+        all_passed.act(vh, BidAction::Pass).unwrap();
+        assert_eq!(all_passed.result(), Some(BiddingResult::AllPassed));
+    }
+
+    #[test]
+    fn ladder() {
+        assert_eq!(BidValue::MIN, bid(18));
+        assert_eq!(BidValue::MAX, bid(264));
+        assert_eq!(bid(18).next(), Some(bid(20)));
+        assert_eq!(bid(22).next(), Some(bid(23)));
+        assert_eq!(bid(264).next(), None);
+        assert_eq!(bid(18).previous(), None);
+        assert_eq!(bid(96).previous(), Some(bid(90)));
+        assert_eq!(
+            BidValue::try_from(19),
+            Err(BiddingError::InvalidValue { value: 19 })
+        );
+        assert_eq!(BidValue::at_most(50), Some(bid(50)));
+        assert_eq!(BidValue::at_most(53), Some(bid(50)));
+        assert_eq!(BidValue::at_most(17), None);
+        let start: Vec<u16> = BidValue::ALL[..10].iter().map(|b| b.value()).collect();
+        assert_eq!(start, vec![18, 20, 22, 23, 24, 27, 30, 33, 35, 36]);
+    }
+}
@@ -0,0 +1,151 @@
+//! This module contains the canonical form of hands regarding the suits. \
+//! Two hands are equivalent if one can be turned into the other by exchanging the suits of the
+//! non-jack cards, e.g. "KB KA KZ" and "KB HA HZ". The jacks keep their suit as they decide the matadors.
+//! Equivalent hands are evaluated the same way except for the base value of the suit game (9, 10, 11, 12),
+//! so tables of hand evaluations only need to store the canonical hands.
+
+use crate::card::{Suit, ALL_SUITS};
+use crate::cardset::CardSet;
+use crate::game::GameType;
+
+/// Mask of the non-jack cards of the Clubs suit.
+const SUIT_NO_JACK_MASK: u32 = 0xF7;
+
+/// A permutation of the suits. The non-jack cards of suit `s` are moved to suit `map(s)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SuitPermutation([Suit; 4]);
+
+impl SuitPermutation {
+    pub const IDENTITY: SuitPermutation = SuitPermutation(ALL_SUITS);
+
+    /// Creates the permutation where `targets[s as usize]` is the new suit of suit s.
+    /// # Returns
+    /// None if a suit is used twice.
+    pub fn new(targets: [Suit; 4]) -> Option<SuitPermutation> {
+        let mut used = [false; 4];
+        for suit in targets {
+            if used[suit as usize] {
+                return None;
+            }
+            used[suit as usize] = true;
+        }
+        Some(SuitPermutation(targets))
+    }
+
+    pub fn map(&self, suit: Suit) -> Suit {
+        self.0[suit as usize]
+    }
+
+    pub fn inverse(&self) -> SuitPermutation {
+        let mut targets = ALL_SUITS;
+        for suit in ALL_SUITS {
+            targets[self.map(suit) as usize] = suit;
+        }
+        SuitPermutation(targets)
+    }
+
+    /// Moves the non-jack cards to their new suits, the jacks stay.
+    pub fn apply(&self, cards: CardSet) -> CardSet {
+        let mut bits = (cards & CardSet::JACKS).bits();
+        for suit in ALL_SUITS {
+            let pattern = (cards.bits() >> (suit as u32 * 8)) & SUIT_NO_JACK_MASK;
+            bits |= pattern << (self.map(suit) as u32 * 8);
+        }
+        CardSet::from_bits(bits)
+    }
+
+    /// Moves the trump suit of a suit game, other game types stay.
+    pub fn apply_game_type(&self, game_type: &GameType) -> GameType {
+        match game_type.trump_suit() {
+            Some(suit) => GameType::from_suit(self.map(suit)),
+            None => *game_type,
+        }
+    }
+}
+
+/// The canonical form of a hand (and Skat), see [canonicalize].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Canonical {
+    /// The canonical hand.
+    pub hand: CardSet,
+    /// The canonical Skat, empty if only the hand was canonicalized.
+    pub skat: CardSet,
+    /// The permutation which maps the original cards to the canonical ones.
+    pub permutation: SuitPermutation,
+    /// The number of distinct hands (or hand and Skat combinations) which have this canonical form.
+    pub class_size: usize,
+}
+
+/// Returns the canonical form of the hand. All hands which only differ by the suits of the
+/// non-jack cards have the same canonical form.
+pub fn canonicalize(hand: CardSet) -> Canonical {
+    canonicalize_with_skat(hand, CardSet::EMPTY)
+}
+
+/// Returns the canonical form of the hand and the Skat, the same permutation is applied to both.
+pub fn canonicalize_with_skat(hand: CardSet, skat: CardSet) -> Canonical {
+    let pattern =
+        |cards: CardSet, suit: Suit| (cards.bits() >> (suit as u32 * 8)) & SUIT_NO_JACK_MASK;
+    let key = |suit: Suit| (pattern(hand, suit) << 8) | pattern(skat, suit);
+
+    // the suit with the highest pattern becomes Clubs, the next Spades and so on
+    let mut suits = ALL_SUITS;
+    suits.sort_by_key(|&suit| std::cmp::Reverse(key(suit)));
+    let mut targets = ALL_SUITS;
+    for (target, &suit) in ALL_SUITS.iter().zip(suits.iter()) {
+        targets[suit as usize] = *target;
+    }
+    let permutation = SuitPermutation(targets);
+
+    // 4! / (product of the factorials of the number of suits with the same pattern)
+    let mut class_size = 24;
+    let mut run = 1;
+    for i in 1..4 {
+        if key(suits[i]) == key(suits[i - 1]) {
+            run += 1;
+            class_size /= run;
+        } else {
+            run = 1;
+        }
+    }
+
+    Canonical {
+        hand: permutation.apply(hand),
+        skat: permutation.apply(skat),
+        permutation,
+        class_size,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand;
+
+    #[test]
+    fn equivalent_hands() {
+        let a = canonicalize(hand!["KB", "KA", "KZ", "H7"]);
+        let b = canonicalize(hand!["KB", "CA", "CZ", "P7"]);
+        assert_eq!(a.hand, b.hand);
+        assert_eq!(a.class_size, 12);
+        assert_eq!(
+            a.permutation.inverse().apply(a.hand),
+            hand!["KB", "KA", "KZ", "H7"]
+        );
+        assert_eq!(
+            b.permutation.inverse().apply_game_type(&GameType::Clubs),
+            GameType::Diamonds
+        );
+
+        assert_eq!(canonicalize(hand!["KA", "PA"]).class_size, 6);
+        assert_eq!(canonicalize(hand!["KB", "PB"]).class_size, 1);
+    }
+
+    #[test]
+    fn with_skat() {
+        let a = canonicalize_with_skat(hand!["KA", "PA"], hand!["K7"]);
+        let b = canonicalize_with_skat(hand!["HA", "CA"], hand!["C7"]);
+        assert_eq!((a.hand, a.skat), (b.hand, b.skat));
+        assert_eq!(a.class_size, 12);
+    }
+}
@@ -1,37 +1,43 @@
 //! This crate contains the playing [Card], its [Suit] and its [Rank].
 
 #![allow(dead_code)]
+use std::cmp::Ordering;
 use std::fmt::{self, Display};
+use std::str::FromStr;
 
-// pub const MAX_BIDDING_VALUE: usize = 96;
-
-// pub enum BiddingValue {
-//     B18 = 18,
-//     B20 = 20,
-//     B22 = 22,
-//     B23 = 23,
-//     B24 = 24,
-//     B27 = 27,
-//     B30 = 30,
-//     B33 = 33,
-//     B35 = 35,
-//     B36 = 36,
-//     B40 = 40,
-//     B44 = 44,
-//     B45 = 45,
-//     B46 = 46,
-//     B48 = 48,
-//     B50 = 50,
-//     B54 = 54,
-//     B55 = 55,
-//     B59 = 59,
-//     B60 = 60,
-//     B72 = 72,
-//     B96 = 96,
-// }
+use crate::error::ParseError;
+use crate::game::GameType;
+use crate::notation::{self, Notation, NotationDisplay};
+
+/// All suits in index order, highest suit first.
+pub const ALL_SUITS: [Suit; 4] = [Suit::Clubs, Suit::Spades, Suit::Hearts, Suit::Diamonds];
+
+/// All ranks in index order.
+pub const ALL_RANKS: [Rank; 8] = [
+    Rank::Seven,
+    Rank::Eight,
+    Rank::Nine,
+    Rank::Jack,
+    Rank::Queen,
+    Rank::King,
+    Rank::Ten,
+    Rank::Ace,
+];
+
+/// All 32 cards in index order, see [Card::index].
+pub const ALL_CARDS: [Card; 32] = {
+    let mut cards = [Card::new(Suit::Clubs, Rank::Seven); 32];
+    let mut i = 0;
+    while i < 32 {
+        cards[i] = Card::new(ALL_SUITS[i / 8], ALL_RANKS[i % 8]);
+        i += 1;
+    }
+    cards
+};
 
 /// This enum represents the four colors of the game Skat and their properties.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Suit {
     Clubs = 0,
     Spades = 1,
@@ -40,7 +46,7 @@ pub enum Suit {
 }
 
 impl Suit {
-    pub fn suit_reiz_factor(&self) -> i16 {
+    pub const fn suit_reiz_factor(&self) -> i16 {
         match self {
             Suit::Clubs => 12,
             Suit::Spades => 11,
@@ -50,72 +56,80 @@ impl Suit {
     }
 
     pub fn suit_name(&self) -> String {
-        match self {
-            Suit::Clubs => "Clubs".to_string(),
-            Suit::Spades => "Spades".to_string(),
-            Suit::Hearts => "Hearts".to_string(),
-            Suit::Diamonds => "Diamonds".to_string(),
-        }
+        self.name(Notation::EnglishNames).to_string()
     }
 
-    pub fn from_usize(value: usize) -> Suit {
-        match value {
-            0 => Suit::Clubs,
-            1 => Suit::Spades,
-            2 => Suit::Hearts,
-            3 => Suit::Diamonds,
-            _ => panic!("Unknown value: {}", value),
-        }
+    /// The symbol or name of the suit in the notation.
+    pub fn name(&self, notation: Notation) -> &'static str {
+        let names = match notation {
+            Notation::German => ["K", "P", "H", "C"],
+            Notation::English => ["C", "S", "H", "D"],
+            Notation::Unicode => ["\u{2663}", "\u{2660}", "\u{2665}", "\u{2666}"],
+            Notation::GermanDeck => ["Eichel", "Grün", "Rot", "Schellen"],
+            Notation::GermanNames => ["Kreuz", "Pik", "Herz", "Karo"],
+            Notation::EnglishNames => ["Clubs", "Spades", "Hearts", "Diamonds"],
+        };
+        names[*self as usize]
+    }
+}
+
+/// Allows input 0 = Clubs, 1 = Spades, 2 = Hearts, 3 = Diamonds
+impl TryFrom<usize> for Suit {
+    type Error = ParseError;
+
+    fn try_from(value: usize) -> Result<Self, ParseError> {
+        ALL_SUITS
+            .get(value)
+            .copied()
+            .ok_or(ParseError::OutOfRange { value, position: 0 })
     }
 }
 
 impl TryFrom<char> for Suit {
-    type Error = String;
+    type Error = ParseError;
 
-    fn try_from(suit_char: char) -> Result<Self, String> {
+    fn try_from(suit_char: char) -> Result<Self, ParseError> {
         match suit_char {
             'K' => Ok(Self::Clubs),
             'P' => Ok(Self::Spades),
             'H' => Ok(Self::Hearts),
             'C' => Ok(Self::Diamonds),
-            _ => Err(
-                "Unknown symbol: use K = Club, P = Spades, H = Hearts, C = Diamonds".to_string(),
-            ),
+            _ => Err(ParseError::UnknownSuit {
+                symbol: suit_char,
+                position: 0,
+            }),
         }
     }
 }
 
+impl NotationDisplay for Suit {
+    fn fmt_notation(&self, f: &mut fmt::Formatter<'_>, notation: Notation) -> fmt::Result {
+        write!(f, "{}", self.name(notation))
+    }
+}
+
 impl Display for Suit {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let c = match self {
-            Self::Clubs => 'K',  // 'C',
-            Self::Spades => 'P', // 'S',
-            Self::Hearts => 'H',
-            Self::Diamonds => 'C', // 'D',
-                                   // Suit::Clubs => '\u{2663}', //'K', // 'C',
-                                   // Suit::Spades => '\u{2660}',   // 'P', 'S',
-                                   // Suit::Hearts => '\u{2665}',   // 'H',
-                                   // Suit::Diamonds => '\u{2666}', //'C',      // 'D',
-        };
-        write!(f, "{c}")
+        self.fmt_notation(f, Notation::German)
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Rank {
-    Seven,
-    Eight,
-    Nine,
-    Jack,
-    Queen,
-    King,
-    Ten,
-    Ace,
+    Seven = 0,
+    Eight = 1,
+    Nine = 2,
+    Jack = 3,
+    Queen = 4,
+    King = 5,
+    Ten = 6,
+    Ace = 7,
 }
 
 impl Rank {
     /// Points when counting the results of the game.
-    pub fn points(&self) -> u16 {
+    pub const fn points(&self) -> u16 {
         match self {
             Self::Seven => 0,
             Self::Eight => 0,
@@ -127,12 +141,72 @@ impl Rank {
             Self::Ace => 11,
         }
     }
+
+    /// Strength of the rank within a suit, higher wins. \
+    /// Null: 7, 8, 9, 10, Jack, Queen, King, Ace \
+    /// Others: 7, 8, 9, Queen, King, 10, Ace; the jack is trump and ranked above the ace.
+    pub fn order(&self, game_type: &GameType) -> u8 {
+        if *game_type == GameType::Null {
+            match self {
+                Self::Seven => 0,
+                Self::Eight => 1,
+                Self::Nine => 2,
+                Self::Ten => 3,
+                Self::Jack => 4,
+                Self::Queen => 5,
+                Self::King => 6,
+                Self::Ace => 7,
+            }
+        } else {
+            match self {
+                Self::Seven => 0,
+                Self::Eight => 1,
+                Self::Nine => 2,
+                Self::Queen => 3,
+                Self::King => 4,
+                Self::Ten => 5,
+                Self::Ace => 6,
+                Self::Jack => 7,
+            }
+        }
+    }
+}
+
+impl Rank {
+    /// The symbol or name of the rank in the notation.
+    pub fn name(&self, notation: Notation) -> &'static str {
+        let names = match notation {
+            Notation::German => ["7", "8", "9", "B", "D", "K", "Z", "A"],
+            Notation::English => ["7", "8", "9", "J", "Q", "K", "T", "A"],
+            Notation::Unicode => ["7", "8", "9", "J", "Q", "K", "10", "A"],
+            Notation::GermanDeck => ["7", "8", "9", "Unter", "Ober", "König", "10", "Daus"],
+            Notation::GermanNames => [
+                "Sieben", "Acht", "Neun", "Bube", "Dame", "König", "Zehn", "Ass",
+            ],
+            Notation::EnglishNames => [
+                "Seven", "Eight", "Nine", "Jack", "Queen", "King", "Ten", "Ace",
+            ],
+        };
+        names[*self as usize]
+    }
+}
+
+/// Allows input 0 = Seven .. 7 = Ace in the order of [ALL_RANKS]
+impl TryFrom<usize> for Rank {
+    type Error = ParseError;
+
+    fn try_from(value: usize) -> Result<Self, ParseError> {
+        ALL_RANKS
+            .get(value)
+            .copied()
+            .ok_or(ParseError::OutOfRange { value, position: 0 })
+    }
 }
 
 impl TryFrom<char> for Rank {
-    type Error = String;
+    type Error = ParseError;
 
-    fn try_from(rank_char: char) -> Result<Self, String> {
+    fn try_from(rank_char: char) -> Result<Self, ParseError> {
         match rank_char {
             '7' => Ok(Self::Seven),
             '8' => Ok(Self::Eight),
@@ -145,27 +219,23 @@ impl TryFrom<char> for Rank {
             'Z' => Ok(Self::Ten),
             'T' => Ok(Self::Ten),
             'A' => Ok(Self::Ace),
-            _ => Err(
-                "Unknown symbol: use 7,8,9, Z/T = ten, B/J = Jack, D/Q = Queen, K = King, A = Ace"
-                    .to_string(),
-            ),
+            _ => Err(ParseError::UnknownRank {
+                symbol: rank_char,
+                position: 0,
+            }),
         }
     }
 }
 
+impl NotationDisplay for Rank {
+    fn fmt_notation(&self, f: &mut fmt::Formatter<'_>, notation: Notation) -> fmt::Result {
+        write!(f, "{}", self.name(notation))
+    }
+}
+
 impl Display for Rank {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let c = match self {
-            Self::Seven => '7',
-            Self::Eight => '8',
-            Self::Nine => '9',
-            Self::Jack => 'B',  // 'J',
-            Self::Queen => 'D', // 'Q',
-            Self::King => 'K',
-            Self::Ten => 'Z', // 'T',
-            Self::Ace => 'A',
-        };
-        write!(f, "{c}")
+        self.fmt_notation(f, Notation::German)
     }
 }
 
@@ -179,11 +249,64 @@ pub struct Card {
 }
 
 impl Card {
-    pub fn new(suit: Suit, rank: Rank) -> Card {
+    pub const fn new(suit: Suit, rank: Rank) -> Card {
         let points = rank.points();
         Card { suit, rank, points }
     }
 
+    /// Returns the canonical number of the card between 0 and 31: suit * 8 + rank. \
+    /// The number is stable and can be used for lookup tables and serialization.
+    pub const fn index(&self) -> usize {
+        self.suit as usize * 8 + self.rank as usize
+    }
+
+    /// Returns the card with the canonical number, see [Card::index].
+    pub const fn from_index(index: usize) -> Option<Card> {
+        if index < ALL_CARDS.len() {
+            Some(ALL_CARDS[index])
+        } else {
+            None
+        }
+    }
+
+    /// Creates the card from its name in German notation, e.g. "KB", at compile time.
+    /// The rank letters J, Q, T are accepted as well. Use the [crate::card!] macro to get a constant.
+    /// # Panics
+    /// If the name is not valid.
+    pub const fn from_german(card_named: &str) -> Card {
+        let bytes = card_named.as_bytes();
+        if bytes.len() != 2 {
+            panic!("Need two symbols");
+        }
+        let suit = match bytes[0] {
+            b'K' => Suit::Clubs,
+            b'P' => Suit::Spades,
+            b'H' => Suit::Hearts,
+            b'C' => Suit::Diamonds,
+            _ => panic!("Unknown suit: use K = Club, P = Spades, H = Hearts, C = Diamonds"),
+        };
+        let rank = match bytes[1] {
+            b'7' => Rank::Seven,
+            b'8' => Rank::Eight,
+            b'9' => Rank::Nine,
+            b'B' | b'J' => Rank::Jack,
+            b'D' | b'Q' => Rank::Queen,
+            b'K' => Rank::King,
+            b'Z' | b'T' => Rank::Ten,
+            b'A' => Rank::Ace,
+            _ => panic!(
+                "Unknown rank: use 7,8,9, Z/T = ten, B/J = Jack, D/Q = Queen, K = King, A = Ace"
+            ),
+        };
+        Card::new(suit, rank)
+    }
+
+    /// Reads a card in the given notation, see [crate::notation]. \
+    /// Use `parse()` to detect the notation.
+    pub fn parse_with(card_named: &str, notation: Notation) -> Result<Card, ParseError> {
+        notation::parse_card(card_named, Some(notation))
+    }
+
     /// High Cards: Jack, Ace, Ten, King  \
     /// Low Cards:  Queen, Nine, Eight, Seven
     pub fn is_high_card(&self) -> bool {
@@ -199,6 +322,53 @@ impl Card {
         }
     }
 
+    /// Jacks are always trump except in Null. In a suit game the cards of the trump suit are trump as well.
+    pub fn is_trump(&self, game_type: &GameType) -> bool {
+        self.trump_rank(game_type).is_some()
+    }
+
+    /// Strength of the card within the trumps, higher wins. \
+    /// The jacks are 10 (Clubs) down to 7 (Diamonds), the cards of the trump suit are 6 (Ace) down to 0 (7).
+    /// # Returns
+    /// None if the card is not a trump in this game.
+    pub fn trump_rank(&self, game_type: &GameType) -> Option<u8> {
+        if *game_type == GameType::Null {
+            None
+        } else if self.rank == Rank::Jack {
+            Some(10 - self.suit as u8)
+        } else if game_type.trump_suit() == Some(self.suit) {
+            Some(self.rank.order(game_type))
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if this card wins against the other card which leads the trick so far. \
+    /// A trump beats any non trump, otherwise the card must follow suit and be of higher rank.
+    pub fn beats(&self, other: &Card, game_type: &GameType) -> bool {
+        match (self.trump_rank(game_type), other.trump_rank(game_type)) {
+            (Some(own), Some(others)) => own > others,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => {
+                self.suit == other.suit && self.rank.order(game_type) > other.rank.order(game_type)
+            }
+        }
+    }
+
+    /// Order for displaying the cards of a hand in this game: \
+    /// trumps first, then the other suits Clubs, Spades, Hearts, Diamonds, each from high to low.
+    pub fn cmp_for(&self, other: &Card, game_type: &GameType) -> Ordering {
+        match (self.trump_rank(game_type), other.trump_rank(game_type)) {
+            (Some(own), Some(others)) => others.cmp(&own),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => (self.suit as u8)
+                .cmp(&(other.suit as u8))
+                .then_with(|| other.rank.order(game_type).cmp(&self.rank.order(game_type))),
+        }
+    }
+
     pub fn suit(&self) -> Suit {
         self.suit
     }
@@ -217,27 +387,77 @@ impl Card {
     }
 }
 
-/// Allows input like "KB"/"CJ" for Jack of Club
+/// Allows input like "KB"/"SJ"/"♣J"/"Eichel-Unter" for Jack of Club, see [crate::notation]
+impl FromStr for Card {
+    type Err = ParseError;
+
+    fn from_str(card_named: &str) -> Result<Self, ParseError> {
+        notation::parse_card(card_named, None)
+    }
+}
+
+/// Allows input like "KB"/"SJ"/"♣J"/"Eichel-Unter" for Jack of Club, see [crate::notation]
 impl TryFrom<&str> for Card {
-    type Error = String;
+    type Error = ParseError;
 
-    fn try_from(card_named: &str) -> Result<Self, String> {
-        if card_named.len() != 2 {
-            return Err("Need two symbols".to_string());
-        }
-        let suit = Suit::try_from(card_named.as_bytes()[0] as char)?;
-        let rank = Rank::try_from(card_named.as_bytes()[1] as char)?;
+    fn try_from(card_named: &str) -> Result<Self, ParseError> {
+        card_named.parse()
+    }
+}
 
-        Ok(Card::new(suit, rank))
+/// "KB", "CJ", "♣J", "Eichel-Unter", "Kreuz-Bube" or "Jack of Clubs"
+impl NotationDisplay for Card {
+    fn fmt_notation(&self, f: &mut fmt::Formatter<'_>, notation: Notation) -> fmt::Result {
+        let (suit, rank) = (self.suit.name(notation), self.rank.name(notation));
+        match notation {
+            Notation::German | Notation::English | Notation::Unicode => write!(f, "{suit}{rank}"),
+            Notation::GermanDeck | Notation::GermanNames => write!(f, "{suit}-{rank}"),
+            Notation::EnglishNames => write!(f, "{rank} of {suit}"),
+        }
     }
 }
 
 impl Display for Card {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}{}", self.suit, self.rank)
+        self.fmt_notation(f, Notation::German)
     }
 }
 
+/// A card constant in German notation, checked at compile time.
+/// ```
+/// use skat_engine::card::{Card, Rank, Suit};
+/// const JACK: Card = skat_engine::card!("KB");
+/// assert_eq!(JACK, Card::new(Suit::Clubs, Rank::Jack));
+/// ```
+/// Invalid names do not compile:
+/// ```compile_fail
+/// let card = skat_engine::card!("KX");
+/// ```
+#[macro_export]
+macro_rules! card {
+    ($card:literal) => {{
+        const CARD: $crate::card::Card = $crate::card::Card::from_german($card);
+        CARD
+    }};
+}
+
+/// A [crate::cardset::CardSet] constant from cards in German notation, checked at compile time.
+/// ```
+/// let hand = skat_engine::hand!["KB", "PB", "HA"];
+/// assert_eq!(hand.num_jacks(), 2);
+/// ```
+/// Invalid names and cards given twice do not compile:
+/// ```compile_fail
+/// let hand = skat_engine::hand!["KB", "PB", "KB"];
+/// ```
+#[macro_export]
+macro_rules! hand {
+    ($($card:literal),* $(,)?) => {{
+        const HAND: $crate::cardset::CardSet = $crate::cardset::CardSet::from_german(&[$($card),*]);
+        HAND
+    }};
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -248,5 +468,70 @@ mod tests {
         let card = Card::new(Suit::Clubs, Rank::Jack);
         let card_test = Card::try_from("KB").unwrap();
         assert_eq!(card, card_test);
+        assert_eq!(
+            Card::try_from("XB"),
+            Err(ParseError::UnknownSuit {
+                symbol: 'X',
+                position: 0
+            })
+        );
+        assert_eq!(
+            Card::try_from("KBB"),
+            Err(ParseError::WrongLength {
+                length: 3,
+                position: 0
+            })
+        );
+    }
+
+    #[test]
+    fn index() {
+        for (i, card) in ALL_CARDS.iter().enumerate() {
+            assert_eq!(card.index(), i);
+            assert_eq!(Card::from_index(i), Some(*card));
+        }
+        assert_eq!(Card::from_index(32), None);
+        assert_eq!(Suit::try_from(2), Ok(Suit::Hearts));
+        assert!(Rank::try_from(8).is_err());
+    }
+
+    #[test]
+    fn display_notation() {
+        let card = Card::new(Suit::Clubs, Rank::Jack);
+        assert_eq!(card.to_string(), "KB");
+        assert_eq!(card.with_notation(Notation::English).to_string(), "CJ");
+        assert_eq!(card.with_notation(Notation::Unicode).to_string(), "♣J");
+        assert_eq!(
+            card.with_notation(Notation::EnglishNames).to_string(),
+            "Jack of Clubs"
+        );
+        for notation in [
+            Notation::German,
+            Notation::English,
+            Notation::Unicode,
+            Notation::GermanDeck,
+            Notation::GermanNames,
+        ] {
+            for card in ALL_CARDS {
+                let name = card.with_notation(notation).to_string();
+                assert_eq!(Card::parse_with(&name, notation), Ok(card));
+            }
+        }
+    }
+
+    #[test]
+    fn trump_order() {
+        // suit game: jacks, then the trump suit
+        assert!(card!("CB").beats(&card!("HA"), &GameType::Hearts));
+        assert!(card!("H7").beats(&card!("KA"), &GameType::Hearts));
+        assert!(card!("HZ").beats(&card!("HK"), &GameType::Hearts));
+        assert!(!card!("PA").beats(&card!("K7"), &GameType::Hearts));
+        // Grand: only jacks are trump
+        assert!(card!("KB").beats(&card!("PB"), &GameType::Grand));
+        assert!(!card!("H7").beats(&card!("KA"), &GameType::Grand));
+        // Null: ten below jack, jacks follow suit
+        assert!(card!("KB").beats(&card!("KZ"), &GameType::Null));
+        assert!(!card!("KB").beats(&card!("HA"), &GameType::Null));
+        assert_eq!(card!("KB").trump_rank(&GameType::Null), None);
     }
 }
@@ -1,117 +1,164 @@
 use std::fmt::{self, Display};
 
 use crate::{
-    card::{Card, Rank, Suit},
+    bidding::BidValue,
+    card::{Card, Rank, Suit, ALL_SUITS},
+    cardset::CardSet,
+    error::ParseError,
+    evaluate::{GrandEvaluation, NullEvaluation},
     game::GameType,
+    matadors::Matadors,
+    notation::{Notation, NotationDisplay},
 };
 
+/// Number of cards a player holds after dealing.
+pub const HAND_SIZE: usize = 10;
+/// Number of cards in the Skat.
+pub const SKAT_SIZE: usize = 2;
+
 /// A CardHolder is either a Player or the Skat. It can hold any number of cards.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CardHolder {
     name: String,
-    cards: Vec<Card>,
+    cards: CardSet,
     // Vorhand = 0, Mittelhand = 1, Hinterhand = 2, Skat = any
     //  player_in_game: u16,
-    /// The number of jacks contained in the cards.
-    num_jacks: u8,
-    /// Array with set 1 for Club, Spade, Heart, Diamond Jack.
-    // TODO check if required, used only once for jack_factor
-    has_jacks: [bool; 4],
+    /// The cards are displayed sorted for this game, see [CardHolder::sort_cards_for].
+    sort_order: Option<GameType>,
 
-    pub reizen_current: u16,
+    pub reizen_current: Option<BidValue>,
     reizen_max: u16,
     game_type: GameType,
 }
 
 impl CardHolder {
-    pub fn new(name: &str, cards: Vec<Card>) -> CardHolder {
-        let mut has_jacks: [bool; 4] = [false; 4];
-        // let num_jacks = cards.iter().filter(|card| card.rank() == Rank::Jack).count();
-        let mut num_jacks = 0;
-        for card in cards.iter().filter(|card| card.rank() == Rank::Jack) {
-            num_jacks += 1;
-            has_jacks[card.suit_value()] = true;
-        }
+    pub fn new(name: &str, cards: CardSet) -> CardHolder {
         CardHolder {
             name: name.to_string(),
             cards,
-            num_jacks,
-            has_jacks,
-            reizen_current: 0,
+            sort_order: None,
+            reizen_current: None,
             reizen_max: u16::MAX,
             game_type: GameType::None,
         }
     }
 
+    /// Like the `TryFrom` conversion, but also checks the number of cards,
+    /// e.g. [HAND_SIZE] for a player or [SKAT_SIZE] for the Skat.
+    pub fn try_from_sized(
+        name: &str,
+        card_names: &Vec<&str>,
+        size: usize,
+    ) -> Result<CardHolder, ParseError> {
+        let card_holder = CardHolder::try_from((name, card_names))?;
+        if card_holder.cards.len() != size {
+            return Err(ParseError::WrongHandSize {
+                expected: size,
+                found: card_holder.cards.len(),
+            });
+        }
+        Ok(card_holder)
+    }
+
     /// This merges a players hand with the skat, so in total 12 cards.
-    // TODO possibly just add the skat
     pub fn new_with_skat(player: &CardHolder, skat: &CardHolder) -> CardHolder {
-        let mut cards = player.cards.clone();
-        cards.extend(skat.cards.clone());
-        CardHolder::new(&player.name, cards)
+        CardHolder::new(&player.name, player.cards | skat.cards)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
     }
 
-    pub fn cards(&self) -> &[Card] {
-        &self.cards
+    pub fn cards(&self) -> CardSet {
+        self.cards
+    }
+
+    /// Returns the cards in display order, sorted if [CardHolder::sort_cards] was called.
+    pub fn cards_in_order(&self) -> Vec<Card> {
+        let mut cards: Vec<Card> = self.cards.iter().collect();
+        if let Some(game_type) = &self.sort_order {
+            cards.sort_by(|a, b| a.cmp_for(b, game_type));
+        }
+        cards
     }
 
     pub fn cards_total_points(&self) -> u16 {
-        self.cards.iter().map(|card| card.rank().points()).sum()
+        self.cards.points() as u16
     }
 
     pub fn game_type(&self) -> &GameType {
         &self.game_type
     }
 
+    /// Removes the card, e.g. when it is played.
+    /// # Returns
+    /// false if the card was not held.
+    pub fn remove_card(&mut self, card: Card) -> bool {
+        self.cards.remove(card)
+    }
+
     /// Checks if the CardHolder has a specific card in his hand.
     pub fn holds_card(&self, suit: Suit, rank: Rank) -> bool {
-        for card in self.cards.iter() {
-            if card.rank() == rank && card.suit() == suit {
-                return true;
-            }
-        }
-        false
+        self.cards.contains(Card::new(suit, rank))
     }
 
     // number of cards of that rank
     pub fn num_cards_rank(&self, rank: Rank) -> usize {
-        self.cards
-            .iter()
-            .filter(|&card| card.rank() == rank)
-            .count()
+        self.cards.num_cards_rank(rank)
     }
 
     // number of cards of that suit without jacks
     pub fn num_cards_suit(&self, suit: Suit) -> usize {
-        self.cards
-            .iter()
-            .filter(|&card| card.rank() != Rank::Jack && card.suit() == suit)
-            .count()
+        self.cards.num_cards_suit(suit)
     }
 
     // number of cards of all suits without jacks
     pub fn num_cards_all_suits(&self) -> (usize, usize, usize, usize) {
-        let (mut clubs, mut spades, mut hearts, mut diamonds) = (0, 0, 0, 0);
-        for card in self.cards.iter().filter(|card| card.rank() != Rank::Jack) {
-            match card.suit() {
-                Suit::Clubs => clubs += 1,
-                Suit::Spades => spades += 1,
-                Suit::Hearts => hearts += 1,
-                Suit::Diamonds => diamonds += 1,
-            }
-        }
-        (clubs, spades, hearts, diamonds)
+        (
+            self.cards.num_cards_suit(Suit::Clubs),
+            self.cards.num_cards_suit(Suit::Spades),
+            self.cards.num_cards_suit(Suit::Hearts),
+            self.cards.num_cards_suit(Suit::Diamonds),
+        )
     }
 
     pub fn num_jacks(&self) -> usize {
-        self.num_jacks as usize
+        self.cards.num_jacks()
     }
 
-    pub fn reizen_max(&mut self) -> u16 {
+    /// The highest bid for these cards, None to pass. The game value is computed once by
+    /// [CardHolder::reizen_v1] for a suit game, a Grand or Null worth more is played instead,
+    /// see [GrandEvaluation] and [NullEvaluation].
+    pub fn reizen_max(&mut self) -> Option<BidValue> {
         if self.reizen_max == u16::MAX {
             _ = self.reizen_v1();
+            let options = [
+                (GameType::Grand, self.grand_evaluation().value()),
+                (GameType::Null, self.null_evaluation().value()),
+            ];
+            for (game_type, value) in options {
+                if let Some(value) = value.filter(|&value| value > self.reizen_max) {
+                    self.reizen_max = value;
+                    self.game_type = game_type;
+                }
+            }
         }
-        self.reizen_max
+        BidValue::at_most(self.reizen_max)
+    }
+
+    /// The strength of the cards for Grand.
+    pub fn grand_evaluation(&self) -> GrandEvaluation {
+        GrandEvaluation::new(self.cards)
+    }
+
+    /// The safety of the cards for Null.
+    pub fn null_evaluation(&self) -> NullEvaluation {
+        NullEvaluation::new(self.cards)
     }
 
     // Reizwert 0 = passe
@@ -121,172 +168,77 @@ impl CardHolder {
         // Trumpfkarten mindestens 10 Punkte
         // Alle Karten mindestens 35 Punkte
         self.reizen_max = 0;
-        // self.game_type = GameType::Null;
 
         let mut suit_card_count = [0; 4];
         let mut suit_card_points = [0; 4];
-        for card in self.cards.iter().filter(|&card| card.rank() != Rank::Jack) {
-            let suit_id = card.suit() as usize;
-            suit_card_count[suit_id] += 1;
-            suit_card_points[suit_id] += card.points();
+        for (i, &suit) in ALL_SUITS.iter().enumerate() {
+            let suit_cards = self.cards.suit_cards(suit);
+            suit_card_count[i] = suit_cards.len();
+            suit_card_points[i] = suit_cards.points();
         }
+        let num_jacks = self.cards.num_jacks();
         let max_count = *suit_card_count.iter().max().unwrap();
         // Check if at least 5 trump
-        let trump_count = self.num_jacks + max_count;
+        let trump_count = num_jacks + max_count;
         if trump_count < 5 {
             return 0;
         }
 
-        let mut aces_count = 0;
+        let aces_count = self.cards.num_cards_rank(Rank::Ace);
         let mut aces = [0; 4];
-        for card in self.cards.iter().filter(|&card| card.rank() == Rank::Ace) {
-            aces_count += 1;
+        for card in self.cards & CardSet::of_rank(Rank::Ace) {
             aces[card.suit() as usize] = 1;
         }
         if trump_count == 5 && aces_count == 0 {
             return 0;
         }
 
-        let mut max_suit = 0;
-        // let mut max_points = 0;
+        let mut max_suit: Option<usize> = None;
         for i in 0..4 {
             // only check larger, if it is the same, then then current value is the higher suit
             if suit_card_count[i] == max_count
-                && (trump_count > 5 || suit_card_points[i] >= 10 || self.num_jacks > 2)
+                && (trump_count > 5 || suit_card_points[i] >= 10 || num_jacks > 2)
             {
-                if max_suit == 0 {
-                    max_suit = i;
-                    // max_points = suit_card_points[i];
-                } else {
+                match max_suit {
+                    None => max_suit = Some(i),
                     // choose suit without aces if other suit has an ace
-                    if aces[i] < aces[max_suit] {
-                        max_suit = i;
-                    }
+                    Some(max) if aces[i] < aces[max] => max_suit = Some(i),
                     // TODO same ace count, then choose what? better points for non-trump color?
+                    Some(_) => {}
                 }
             }
         }
-        if max_suit == 0 {
+        let Some(max_suit) = max_suit else {
             return 0;
-        }
-        if self.num_jacks + max_count == 5 && aces_count - aces[max_suit] == 0 {
+        };
+        if num_jacks + max_count == 5 && aces_count - aces[max_suit] == 0 {
             return 0;
         }
 
         // Calc Reizwert
-        let jack_factor = self.reiz_factor();
-        let factor = jack_factor.abs() + 1;
-        let suit = Suit::from_usize(max_suit);
-        let suit_reiz_factor = suit.suit_reiz_factor();
-
+        let suit = ALL_SUITS[max_suit];
+        let suit_reiz_factor = suit.suit_reiz_factor() as u16;
         self.game_type = GameType::from_suit(suit);
-        self.reizen_max = (suit_reiz_factor * factor) as u16;
+        let factor = self.matadors(&self.game_type).multiplier();
+
+        self.reizen_max = suit_reiz_factor * factor;
         self.reizen_max
     }
 
-    /// Calc Factor with or without jacks.
-    /// # Returns
-    /// A positive number if the first jack is held, else a negative number,\
-    /// e.g. 3rd ('without two')= -2.
-    fn reiz_factor(&self) -> i16 {
-        match self.num_jacks {
-            0 => -4,
-            4 => 4,
-            _ => {
-                if self.has_jacks[0] {
-                    if self.has_jacks[1] {
-                        if self.has_jacks[2] {
-                            3
-                        } else {
-                            2
-                        }
-                    } else {
-                        1
-                    }
-                } else if !self.has_jacks[1] {
-                    if !self.has_jacks[2] {
-                        -3
-                    } else {
-                        -2
-                    }
-                } else {
-                    -1
-                }
-            }
-        }
+    /// The matadors of the cards in this game, see [Matadors].
+    pub fn matadors(&self, game_type: &GameType) -> Matadors {
+        Matadors::new(self.cards, game_type)
     }
 
+    /// Sorts the cards for better display: jacks first, then the suits, each by rank.
     pub fn sort_cards(&mut self) {
-        // TODO move rank_order to Rank, rank_order_color, rank_order_null
-        self.cards.sort_by(|a, b| {
-            let rank_order = |rank: &Rank| match rank {
-                Rank::Jack => 9,
-                Rank::Ace => 8,
-                Rank::Ten => 7,
-                Rank::King => 6,
-                Rank::Queen => 5,
-                Rank::Nine => 3,
-                Rank::Eight => 2,
-                Rank::Seven => 1,
-            };
-
-            // TODO move suit_order to Suit or remove as it has a value already
-            let suit_order = |suit: &Suit| match suit {
-                Suit::Clubs => 4,
-                Suit::Spades => 3,
-                Suit::Hearts => 2,
-                Suit::Diamonds => 1,
-            };
-
-            if a.rank() == Rank::Jack && b.rank() == Rank::Jack {
-                suit_order(&b.suit()).cmp(&suit_order(&a.suit()))
-            } else if a.rank() == Rank::Jack {
-                std::cmp::Ordering::Less
-            } else if b.rank() == Rank::Jack {
-                std::cmp::Ordering::Greater
-            } else if a.suit() == b.suit() {
-                rank_order(&b.rank()).cmp(&rank_order(&a.rank()))
-            } else {
-                suit_order(&b.suit()).cmp(&suit_order(&a.suit()))
-            }
-        });
+        self.sort_cards_for(&GameType::None);
     }
 
-    // pub fn sort_for_color(cards: &mut [Card]) {
-    //     // TODO move rank_order to Rank, rank_order_color, rank_order_null
-    //     cards.sort_by(|a, b| {
-    //         let rank_order = |rank: &Rank| match rank {
-    //             Rank::Jack => 9,
-    //             Rank::Ace => 8,
-    //             Rank::Ten => 7,
-    //             Rank::King => 6,
-    //             Rank::Queen => 5,
-    //             Rank::Nine => 3,
-    //             Rank::Eight => 2,
-    //             Rank::Seven => 1,
-    //         };
-    //
-    //         // TODO move suit_order to Suit or remove as it has a value already
-    //         let suit_order = |suit: &Suit| match suit {
-    //             Suit::Clubs => 4,
-    //             Suit::Spades => 3,
-    //             Suit::Hearts => 2,
-    //             Suit::Diamonds => 1,
-    //         };
-    //
-    //         if a.rank == Rank::Jack && b.rank == Rank::Jack {
-    //             suit_order(&b.suit).cmp(&suit_order(&a.suit))
-    //         } else if a.rank == Rank::Jack {
-    //             std::cmp::Ordering::Less
-    //         } else if b.rank == Rank::Jack {
-    //             std::cmp::Ordering::Greater
-    //         } else if a.suit == b.suit {
-    //             rank_order(&b.rank).cmp(&rank_order(&a.rank))
-    //         } else {
-    //             suit_order(&b.suit).cmp(&suit_order(&a.suit))
-    //         }
-    //     });
-    // }
+    /// Sorts the cards as they rank in the given game: trumps first, then the other suits, each by rank.
+    pub fn sort_cards_for(&mut self, game_type: &GameType) {
+        self.sort_order = Some(*game_type);
+    }
 
     /// Finds the trump cards and their points (jacks count as card and 2). \
     /// Trump cards are all jacks and the suit with the most number of cards. \
@@ -297,11 +249,10 @@ impl CardHolder {
     pub fn trump_suit_cards(&self) -> (Suit, usize, usize) {
         let mut suit_card_count = [0; 4];
         let mut suit_card_points = [0; 4];
-        // let mut jacks_count = 0;
-        for card in self.cards.iter().filter(|&card| card.rank() != Rank::Jack) {
-            let suit_id = card.suit() as usize;
-            suit_card_count[suit_id] += 1;
-            suit_card_points[suit_id] += card.points();
+        for (i, &suit) in ALL_SUITS.iter().enumerate() {
+            let suit_cards = self.cards.suit_cards(suit);
+            suit_card_count[i] = suit_cards.len();
+            suit_card_points[i] = suit_cards.points();
         }
         let max_count = *suit_card_count.iter().max().unwrap();
         let mut max_suit = 0;
@@ -314,35 +265,102 @@ impl CardHolder {
             }
         }
 
-        let suit = Suit::from_usize(max_suit);
+        let suit = ALL_SUITS[max_suit];
         // (suit, max_count + jacks_count, max_value + jacks_count * 2)
         (suit, max_count, max_points)
     }
 
     pub fn cards_to_string(&self) -> String {
-        let cards_str: Vec<String> = self.cards.iter().map(|c| c.to_string()).collect();
+        self.cards_to_string_with(Notation::German)
+    }
+
+    pub fn cards_to_string_with(&self, notation: Notation) -> String {
+        let cards_str: Vec<String> = self
+            .cards_in_order()
+            .iter()
+            .map(|c| c.with_notation(notation).to_string())
+            .collect();
         cards_str.join(", ")
     }
 }
 
-/// Allows input like "KB"/"CJ" for Jack of Club
+/// Allows input like "KB"/"SJ"/"♣J" for Jack of Club, see [crate::notation]
 impl TryFrom<(&str, &Vec<&str>)> for CardHolder {
-    type Error = String;
+    type Error = ParseError;
 
-    fn try_from(tuple: (&str, &Vec<&str>)) -> Result<Self, String> {
-        let mut cards = Vec::new();
-        for &card_name in tuple.1 {
-            let card = Card::try_from(card_name)?;
-            cards.push(card);
-        }
+    fn try_from(tuple: (&str, &Vec<&str>)) -> Result<Self, ParseError> {
+        let cards = CardSet::from_card_names(tuple.1, None)?;
 
         let card_holder = Self::new(tuple.0, cards);
         Ok(card_holder)
     }
 }
 
+impl NotationDisplay for CardHolder {
+    fn fmt_notation(&self, f: &mut fmt::Formatter<'_>, notation: Notation) -> fmt::Result {
+        write!(
+            f,
+            "{}: [{}]",
+            self.name,
+            self.cards_to_string_with(notation)
+        )
+    }
+}
+
 impl Display for CardHolder {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}: [{}]", self.name, self.cards_to_string())
+        self.fmt_notation(f, Notation::German)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_from_errors() {
+        let hand = CardHolder::try_from(("Player", &vec!["KB", "PB", "HX"]));
+        assert_eq!(
+            hand.unwrap_err(),
+            ParseError::UnknownRank {
+                symbol: 'X',
+                position: 2
+            }
+        );
+        let hand = CardHolder::try_from(("Player", &vec!["KB", "PB", "KB"]));
+        assert_eq!(hand.unwrap_err().position(), Some(2));
+        let skat = CardHolder::try_from_sized("Skat", &vec!["KB"], SKAT_SIZE);
+        assert_eq!(
+            skat.unwrap_err(),
+            ParseError::WrongHandSize {
+                expected: 2,
+                found: 1
+            }
+        );
+    }
+
+    #[test]
+    fn reizen_max() {
+        let mut clubs = CardHolder::new(
+            "Player",
+            crate::hand!["KB", "HB", "KA", "KK", "KD", "K9", "K8", "P7", "H7", "CA"],
+        );
+        assert_eq!(clubs.reizen_max(), BidValue::new(24));
+        assert_eq!(clubs.game_type(), &GameType::Clubs);
+
+        // Grand with 3 plays 4 beats Clubs with 3 plays 4
+        let mut grand = CardHolder::new(
+            "Player",
+            crate::hand!["KB", "PB", "HB", "KA", "KZ", "KK", "KD", "K9", "PA", "HA"],
+        );
+        assert_eq!(grand.reizen_max(), BidValue::new(96));
+        assert_eq!(grand.game_type(), &GameType::Grand);
+
+        let mut null = CardHolder::new(
+            "Player",
+            crate::hand!["K7", "K9", "KB", "P7", "P8", "P9", "H7", "H9", "C7", "C8"],
+        );
+        assert_eq!(null.reizen_max(), BidValue::new(59));
+        assert_eq!(null.game_type(), &GameType::Null);
     }
 }
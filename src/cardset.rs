@@ -0,0 +1,407 @@
+//! This module contains the [CardSet], a set of cards stored as a bit mask.
+
+use std::fmt::{self, Display};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::card::{Card, Rank, Suit, ALL_CARDS, ALL_RANKS};
+use crate::error::ParseError;
+use crate::notation::{self, Notation, NotationDisplay};
+
+/// Bit mask of all cards of one suit (8 bits, Clubs).
+const SUIT_MASK: u32 = 0xFF;
+/// Bit mask of all cards of one rank (one bit per suit, Seven).
+const RANK_MASK: u32 = 0x0101_0101;
+
+/// Bit position of the card is its [Card::index].
+const fn bit(card: Card) -> u32 {
+    1 << card.index()
+}
+
+fn card_from_bit(index: u32) -> Card {
+    ALL_CARDS[index as usize]
+}
+
+/// A set of cards, e.g. the hand of a player or the Skat. \
+/// The cards are stored as 32 bit mask, one bit per [Card::index], so the set is `Copy` and
+/// counting cards of a suit or rank is a single popcount.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CardSet(u32);
+
+impl CardSet {
+    pub const EMPTY: CardSet = CardSet(0);
+    pub const ALL: CardSet = CardSet(u32::MAX);
+    pub const JACKS: CardSet = CardSet(RANK_MASK << Rank::Jack as u32);
+
+    pub const fn new() -> CardSet {
+        CardSet::EMPTY
+    }
+
+    pub const fn from_bits(bits: u32) -> CardSet {
+        CardSet(bits)
+    }
+
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// All 8 cards of the suit including the jack.
+    pub fn of_suit(suit: Suit) -> CardSet {
+        CardSet(SUIT_MASK << (suit as u32 * 8))
+    }
+
+    /// All 4 cards of the rank.
+    pub fn of_rank(rank: Rank) -> CardSet {
+        CardSet(RANK_MASK << rank as u32)
+    }
+
+    /// Adds the card. Returns false if it was already contained.
+    pub const fn insert(&mut self, card: Card) -> bool {
+        let contained = self.contains(card);
+        self.0 |= bit(card);
+        !contained
+    }
+
+    /// Removes the card. Returns false if it was not contained.
+    pub fn remove(&mut self, card: Card) -> bool {
+        let contained = self.contains(card);
+        self.0 &= !bit(card);
+        contained
+    }
+
+    pub const fn contains(&self, card: Card) -> bool {
+        self.0 & bit(card) != 0
+    }
+
+    pub const fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn union(&self, other: CardSet) -> CardSet {
+        CardSet(self.0 | other.0)
+    }
+
+    pub fn intersection(&self, other: CardSet) -> CardSet {
+        CardSet(self.0 & other.0)
+    }
+
+    /// The cards of this set which are not in other.
+    pub fn difference(&self, other: CardSet) -> CardSet {
+        CardSet(self.0 & !other.0)
+    }
+
+    pub fn is_subset(&self, other: CardSet) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    pub fn is_disjoint(&self, other: CardSet) -> bool {
+        self.0 & other.0 == 0
+    }
+
+    /// The cards of that suit without jacks.
+    pub fn suit_cards(&self, suit: Suit) -> CardSet {
+        CardSet(self.0 & CardSet::of_suit(suit).0 & !CardSet::JACKS.0)
+    }
+
+    /// Number of cards of that suit without jacks.
+    pub fn num_cards_suit(&self, suit: Suit) -> usize {
+        self.suit_cards(suit).len()
+    }
+
+    /// Number of cards of that rank.
+    pub fn num_cards_rank(&self, rank: Rank) -> usize {
+        (self.0 & CardSet::of_rank(rank).0).count_ones() as usize
+    }
+
+    pub fn num_jacks(&self) -> usize {
+        self.num_cards_rank(Rank::Jack)
+    }
+
+    /// Total points of the cards when counting the results of the game.
+    pub fn points(&self) -> usize {
+        ALL_RANKS
+            .iter()
+            .map(|&rank| self.num_cards_rank(rank) * rank.points() as usize)
+            .sum()
+    }
+
+    /// Creates the set from German card names at compile time, see [Card::from_german].
+    /// Use the [crate::hand] macro to get a constant.
+    /// # Panics
+    /// If a name is not valid or a card is given twice.
+    pub const fn from_german(card_names: &[&str]) -> CardSet {
+        let mut set = CardSet::new();
+        let mut i = 0;
+        while i < card_names.len() {
+            if !set.insert(Card::from_german(card_names[i])) {
+                panic!("Card is given twice");
+            }
+            i += 1;
+        }
+        set
+    }
+
+    /// Reads a hand like "KB PB HA HZ" in the given notation, see [crate::notation]. \
+    /// Use `parse()` to detect the notation.
+    pub fn parse_with(hand: &str, notation: Notation) -> Result<CardSet, ParseError> {
+        CardSet::from_card_names(&notation::split_hand(hand), Some(notation))
+    }
+
+    /// Reads the cards, each given once.
+    pub(crate) fn from_card_names(
+        card_names: &[&str],
+        notation: Option<Notation>,
+    ) -> Result<CardSet, ParseError> {
+        let mut set = CardSet::new();
+        for (position, card) in notation::parse_cards(card_names, notation)?
+            .into_iter()
+            .enumerate()
+        {
+            if !set.insert(card) {
+                return Err(ParseError::DuplicateCard { card, position });
+            }
+        }
+        Ok(set)
+    }
+
+    /// Iterates the cards in ascending bit order (Clubs 7 first, Diamonds Ace last). \
+    /// Use `rev()` for descending order.
+    pub fn iter(&self) -> Iter {
+        Iter(self.0)
+    }
+
+    /// Iterates all subsets of this set with k cards, e.g. all possible Skats of the unknown cards.
+    pub fn subsets(&self, k: usize) -> Subsets {
+        Subsets::new(*self, k)
+    }
+
+    /// Iterates the cards in the given order. Cards of the order which are not in this set are skipped,
+    /// cards of this set which are not in the order are not returned.
+    pub fn iter_in<'a>(&self, order: &'a [Card]) -> impl Iterator<Item = Card> + 'a {
+        let set = *self;
+        order
+            .iter()
+            .copied()
+            .filter(move |&card| set.contains(card))
+    }
+}
+
+/// Iterator over the cards of a [CardSet] in bit order.
+#[derive(Debug, Clone)]
+pub struct Iter(u32);
+
+impl Iterator for Iter {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Card> {
+        if self.0 == 0 {
+            return None;
+        }
+        let index = self.0.trailing_zeros();
+        self.0 &= self.0 - 1;
+        Some(card_from_bit(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Iter {
+    fn next_back(&mut self) -> Option<Card> {
+        if self.0 == 0 {
+            return None;
+        }
+        let index = 31 - self.0.leading_zeros();
+        self.0 &= !(1 << index);
+        Some(card_from_bit(index))
+    }
+}
+
+impl ExactSizeIterator for Iter {}
+
+/// Iterator over the subsets of a [CardSet] with a fixed number of cards, see [CardSet::subsets].
+#[derive(Debug, Clone)]
+pub struct Subsets {
+    cards: Vec<Card>,
+    /// Positions in cards of the next subset, None when done.
+    positions: Option<Vec<usize>>,
+}
+
+impl Subsets {
+    fn new(set: CardSet, k: usize) -> Subsets {
+        let cards: Vec<Card> = set.iter().collect();
+        let positions = (k <= cards.len()).then(|| (0..k).collect());
+        Subsets { cards, positions }
+    }
+}
+
+impl Iterator for Subsets {
+    type Item = CardSet;
+
+    fn next(&mut self) -> Option<CardSet> {
+        let positions = self.positions.as_mut()?;
+        let subset = positions.iter().map(|&p| self.cards[p]).collect();
+
+        // advance to the next combination: increase the last position which can be increased
+        let (n, k) = (self.cards.len(), positions.len());
+        match (0..k).rev().find(|&i| positions[i] < n - k + i) {
+            Some(i) => {
+                positions[i] += 1;
+                for j in i + 1..k {
+                    positions[j] = positions[j - 1] + 1;
+                }
+            }
+            None => self.positions = None,
+        }
+        Some(subset)
+    }
+}
+
+impl IntoIterator for CardSet {
+    type Item = Card;
+    type IntoIter = Iter;
+
+    fn into_iter(self) -> Iter {
+        self.iter()
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = Card>>(iter: I) -> Self {
+        let mut set = CardSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<Card> for CardSet {
+    fn extend<I: IntoIterator<Item = Card>>(&mut self, iter: I) {
+        for card in iter {
+            self.insert(card);
+        }
+    }
+}
+
+impl From<Card> for CardSet {
+    fn from(card: Card) -> Self {
+        CardSet(bit(card))
+    }
+}
+
+impl BitOr for CardSet {
+    type Output = CardSet;
+
+    fn bitor(self, rhs: CardSet) -> CardSet {
+        self.union(rhs)
+    }
+}
+
+impl BitOrAssign for CardSet {
+    fn bitor_assign(&mut self, rhs: CardSet) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for CardSet {
+    type Output = CardSet;
+
+    fn bitand(self, rhs: CardSet) -> CardSet {
+        self.intersection(rhs)
+    }
+}
+
+impl BitAndAssign for CardSet {
+    fn bitand_assign(&mut self, rhs: CardSet) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl Sub for CardSet {
+    type Output = CardSet;
+
+    fn sub(self, rhs: CardSet) -> CardSet {
+        self.difference(rhs)
+    }
+}
+
+impl SubAssign for CardSet {
+    fn sub_assign(&mut self, rhs: CardSet) {
+        self.0 &= !rhs.0;
+    }
+}
+
+impl Not for CardSet {
+    type Output = CardSet;
+
+    /// All cards of the game which are not in this set.
+    fn not(self) -> CardSet {
+        CardSet(!self.0)
+    }
+}
+
+/// Allows input like "KB PB HA HZ" or "♣J, ♠J, ♥A, ♥10", see [crate::notation]
+impl FromStr for CardSet {
+    type Err = ParseError;
+
+    fn from_str(hand: &str) -> Result<Self, ParseError> {
+        CardSet::from_card_names(&notation::split_hand(hand), None)
+    }
+}
+
+impl NotationDisplay for CardSet {
+    fn fmt_notation(&self, f: &mut fmt::Formatter<'_>, notation: Notation) -> fmt::Result {
+        let cards_str: Vec<String> = self
+            .iter()
+            .map(|c| c.with_notation(notation).to_string())
+            .collect();
+        write!(f, "{}", cards_str.join(", "))
+    }
+}
+
+impl Display for CardSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_notation(f, Notation::German)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts() {
+        let cards = crate::hand!["KB", "PB", "KA", "KZ", "K7", "HA"];
+        assert_eq!(cards.len(), 6);
+        assert_eq!(cards.num_jacks(), 2);
+        assert_eq!(cards.num_cards_suit(Suit::Clubs), 3);
+        assert_eq!(cards.num_cards_rank(Rank::Ace), 2);
+        assert_eq!(cards.points(), 2 + 2 + 11 + 10 + 11);
+        assert_eq!((cards & CardSet::JACKS).len(), 2);
+        assert_eq!((cards - CardSet::JACKS).len(), 4);
+    }
+
+    #[test]
+    fn iter_order() {
+        let cards = CardSet::of_suit(Suit::Hearts);
+        let first = cards.iter().next().unwrap();
+        let last = cards.iter().next_back().unwrap();
+        assert_eq!(first, Card::new(Suit::Hearts, Rank::Seven));
+        assert_eq!(last, Card::new(Suit::Hearts, Rank::Ace));
+        assert_eq!(CardSet::ALL.iter().count(), 32);
+    }
+
+    #[test]
+    fn subsets() {
+        let cards = crate::hand!["KB", "PB", "HB", "CB"];
+        assert_eq!(cards.subsets(2).count(), 6);
+        assert!(cards.subsets(2).all(|s| s.len() == 2 && s.is_subset(cards)));
+        assert_eq!(cards.subsets(0).count(), 1);
+        assert_eq!(cards.subsets(5).count(), 0);
+    }
+}
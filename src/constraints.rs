@@ -0,0 +1,366 @@
+//! This module deals the cards at random under constraints, e.g. for Monte Carlo simulations
+//! from the view of one player.
+//!
+//! Constraints which only restrict who may hold a card ([DealConstraints::hand],
+//! [DealConstraints::holds], [DealConstraints::excludes], [DealConstraints::void]) are sampled
+//! exactly and uniformly without rejection: the number of deals for each partial assignment is
+//! counted and each card is given to a holder with the probability of the deals that remain.
+//! Constraints on the number of cards ([DealConstraints::at_least], [DealConstraints::at_most])
+//! are checked afterwards and the deal is sampled again if they fail.
+
+use rand::Rng;
+
+use crate::card::{Suit, ALL_CARDS};
+use crate::cardholder::{HAND_SIZE, SKAT_SIZE};
+use crate::cardset::CardSet;
+use crate::deal::{Deal, Holder};
+use crate::deck::Deck;
+use crate::error::DealError;
+use crate::seat::PlayerId;
+
+/// Number of tries to fulfill the count constraints before giving up.
+const DEFAULT_MAX_ATTEMPTS: usize = 10_000;
+
+/// Bit of the holder in the mask of allowed holders of a card.
+fn holder_bit(holder: Holder) -> u8 {
+    match holder {
+        Holder::Player(player) => 1 << player.index(),
+        Holder::Skat => 1 << 3,
+    }
+}
+
+/// The holder must hold between min and max of the cards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CountConstraint {
+    holder: Holder,
+    cards: CardSet,
+    min: usize,
+    max: usize,
+}
+
+impl CountConstraint {
+    fn is_fulfilled(&self, deal: &Deal) -> bool {
+        let count = (deal.cards(self.holder) & self.cards).len();
+        self.min <= count && count <= self.max
+    }
+}
+
+/// Builder of the constraints for a random deal.
+/// ```
+/// use skat_engine::card::Suit;
+/// use skat_engine::constraints::DealConstraints;
+/// use skat_engine::deal::Holder;
+/// use skat_engine::seat::PlayerId;
+/// use skat_engine::{hand, shuffle::SkatRng};
+///
+/// let [first, second, third] = PlayerId::ALL;
+/// let deal = DealConstraints::new()
+///     .hand(Holder::Player(first), hand!["KB", "PB", "KA", "KZ", "KK", "KD", "K9", "K8", "K7", "PA"])
+///     .void(third, Suit::Hearts)
+///     .at_least(Holder::Player(second), skat_engine::cardset::CardSet::JACKS, 1)
+///     .sample(&mut SkatRng::new(1))
+///     .unwrap();
+/// assert_eq!(deal.player(third).num_cards_suit(Suit::Hearts), 0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DealConstraints {
+    /// The holders which may get the card, indexed by [crate::card::Card::index].
+    allowed: [u8; 32],
+    counts: Vec<CountConstraint>,
+    max_attempts: usize,
+}
+
+impl Default for DealConstraints {
+    fn default() -> Self {
+        DealConstraints::new()
+    }
+}
+
+impl DealConstraints {
+    /// No constraints, every deal is possible.
+    pub fn new() -> DealConstraints {
+        DealConstraints {
+            allowed: [0b1111; 32],
+            counts: Vec::new(),
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+        }
+    }
+
+    /// The holder gets exactly these cards.
+    pub fn hand(self, holder: Holder, cards: CardSet) -> DealConstraints {
+        self.holds(holder, cards).excludes(holder, !cards)
+    }
+
+    /// The holder gets these cards and maybe others.
+    pub fn holds(mut self, holder: Holder, cards: CardSet) -> DealConstraints {
+        for card in cards {
+            self.allowed[card.index()] &= holder_bit(holder);
+        }
+        self
+    }
+
+    /// The holder gets none of these cards.
+    pub fn excludes(mut self, holder: Holder, cards: CardSet) -> DealConstraints {
+        for card in cards {
+            self.allowed[card.index()] &= !holder_bit(holder);
+        }
+        self
+    }
+
+    /// The player has no card of the suit, the jacks are not counted as suit cards.
+    pub fn void(self, player: PlayerId, suit: Suit) -> DealConstraints {
+        self.excludes(Holder::Player(player), CardSet::ALL.suit_cards(suit))
+    }
+
+    /// The holder gets at least n of the cards.
+    pub fn at_least(self, holder: Holder, cards: CardSet, n: usize) -> DealConstraints {
+        self.between(holder, cards, n, usize::MAX)
+    }
+
+    /// The holder gets at most n of the cards.
+    pub fn at_most(self, holder: Holder, cards: CardSet, n: usize) -> DealConstraints {
+        self.between(holder, cards, 0, n)
+    }
+
+    fn between(
+        mut self,
+        holder: Holder,
+        cards: CardSet,
+        min: usize,
+        max: usize,
+    ) -> DealConstraints {
+        self.counts.push(CountConstraint {
+            holder,
+            cards,
+            min,
+            max,
+        });
+        self
+    }
+
+    /// Number of attempts to fulfill the count constraints, 10,000 by default.
+    pub fn max_attempts(mut self, attempts: usize) -> DealConstraints {
+        self.max_attempts = attempts;
+        self
+    }
+
+    /// Prepares the sampling of many deals.
+    /// # Errors
+    /// [DealError::Unsatisfiable] if no deal fulfills the constraints on who may hold which card,
+    /// or a count constraint can never be fulfilled.
+    pub fn sampler(&self) -> Result<DealSampler, DealError> {
+        let sampler = DealSampler::new(self)?;
+        for count in self.counts.iter() {
+            let bit = holder_bit(count.holder);
+            let possible = count
+                .cards
+                .iter()
+                .filter(|card| self.allowed[card.index()] & bit != 0)
+                .count();
+            let forced = count
+                .cards
+                .iter()
+                .filter(|card| self.allowed[card.index()] == bit)
+                .count();
+            if count.min > possible.min(count.holder.size()) || forced > count.max {
+                return Err(DealError::Unsatisfiable);
+            }
+        }
+        Ok(sampler)
+    }
+
+    /// Samples one deal uniformly from all deals fulfilling the constraints.
+    /// Use [DealConstraints::sampler] for many deals.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<Deal, DealError> {
+        self.sampler()?.sample(rng)
+    }
+
+    /// Samples a deck which gives a deal fulfilling the constraints, see [Deck::from_deal].
+    pub fn sample_deck<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<Deck, DealError> {
+        self.sample(rng).map(|deal| Deck::from_deal(&deal))
+    }
+}
+
+/// Index of the remaining capacity of the players 0..11 each, the Skat gets the rest.
+fn capacity_index(capacity: [usize; 3]) -> usize {
+    (capacity[0] * (HAND_SIZE + 1) + capacity[1]) * (HAND_SIZE + 1) + capacity[2]
+}
+
+const CAPACITY_STATES: usize = (HAND_SIZE + 1) * (HAND_SIZE + 1) * (HAND_SIZE + 1);
+
+/// Samples deals for [DealConstraints], see [DealConstraints::sampler].
+#[derive(Debug, Clone)]
+pub struct DealSampler {
+    allowed: [u8; 32],
+    counts: Vec<CountConstraint>,
+    max_attempts: usize,
+    /// `ways[i][c]`: number of ways to deal the cards i.. when the players can take
+    /// the cards given by capacity c and the Skat the rest.
+    ways: Vec<[u128; CAPACITY_STATES]>,
+}
+
+impl DealSampler {
+    fn new(constraints: &DealConstraints) -> Result<DealSampler, DealError> {
+        let n = ALL_CARDS.len();
+        let mut ways = vec![[0u128; CAPACITY_STATES]; n + 1];
+        ways[n][capacity_index([0; 3])] = 1;
+        for i in (0..n).rev() {
+            let remaining = n - i;
+            for c0 in 0..=HAND_SIZE {
+                for c1 in 0..=HAND_SIZE {
+                    for c2 in 0..=HAND_SIZE {
+                        let capacity = [c0, c1, c2];
+                        let Some(skat) = remaining.checked_sub(c0 + c1 + c2) else {
+                            continue;
+                        };
+                        if skat > SKAT_SIZE {
+                            continue;
+                        }
+                        ways[i][capacity_index(capacity)] = (0..4)
+                            .filter(|&h| constraints.allowed[i] & (1 << h) != 0)
+                            .filter_map(|h| take(capacity, skat, h))
+                            .map(|c| ways[i + 1][capacity_index(c)])
+                            .sum();
+                    }
+                }
+            }
+        }
+        if ways[0][capacity_index([HAND_SIZE; 3])] == 0 {
+            return Err(DealError::Unsatisfiable);
+        }
+        Ok(DealSampler {
+            allowed: constraints.allowed,
+            counts: constraints.counts.clone(),
+            max_attempts: constraints.max_attempts,
+            ways,
+        })
+    }
+
+    /// Number of deals in which each card goes to an allowed holder, the count constraints
+    /// are not considered.
+    pub fn count(&self) -> u128 {
+        self.ways[0][capacity_index([HAND_SIZE; 3])]
+    }
+
+    /// Samples one deal uniformly from all deals fulfilling the constraints.
+    /// # Errors
+    /// [DealError::TooManyAttempts] if the count constraints were not fulfilled in time.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<Deal, DealError> {
+        for _ in 0..self.max_attempts.max(1) {
+            let deal = self.sample_allowed(rng);
+            if self.counts.iter().all(|count| count.is_fulfilled(&deal)) {
+                return Ok(deal);
+            }
+        }
+        Err(DealError::TooManyAttempts {
+            attempts: self.max_attempts,
+        })
+    }
+
+    /// Samples a deal where each card goes to an allowed holder, each such deal is equally likely.
+    fn sample_allowed<R: Rng + ?Sized>(&self, rng: &mut R) -> Deal {
+        let mut capacity = [HAND_SIZE; 3];
+        let mut skat_capacity = SKAT_SIZE;
+        let mut players = [CardSet::new(); 3];
+        let mut skat = CardSet::new();
+        for (i, &card) in ALL_CARDS.iter().enumerate() {
+            let mut choice = rng.random_range(0..self.ways[i][capacity_index(capacity)]);
+            for h in (0..4).filter(|&h| self.allowed[i] & (1 << h) != 0) {
+                let Some(next) = take(capacity, skat_capacity, h) else {
+                    continue;
+                };
+                let ways = self.ways[i + 1][capacity_index(next)];
+                if choice >= ways {
+                    choice -= ways;
+                    continue;
+                }
+                if h == 3 {
+                    skat.insert(card);
+                    skat_capacity -= 1;
+                } else {
+                    players[h].insert(card);
+                }
+                capacity = next;
+                break;
+            }
+        }
+        Deal::new_unchecked(players, skat)
+    }
+}
+
+/// The capacity of the players after holder h (3 for the Skat) takes a card, None if it is full.
+fn take(capacity: [usize; 3], skat: usize, h: usize) -> Option<[usize; 3]> {
+    let mut next = capacity;
+    match h {
+        3 => (skat > 0).then_some(next),
+        _ => {
+            next[h] = capacity[h].checked_sub(1)?;
+            Some(next)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand;
+    use crate::shuffle::SkatRng;
+
+    const PLAYERS: [Holder; 3] = [
+        Holder::Player(PlayerId::ALL[0]),
+        Holder::Player(PlayerId::ALL[1]),
+        Holder::Player(PlayerId::ALL[2]),
+    ];
+
+    #[test]
+    fn constraints() {
+        let hand = hand!["KB", "PB", "KA", "KZ", "KK", "KD", "K9", "K8", "K7", "PA"];
+        let constraints = DealConstraints::new()
+            .hand(PLAYERS[0], hand)
+            .void(PlayerId::ALL[2], Suit::Hearts)
+            .excludes(Holder::Skat, CardSet::JACKS);
+        let sampler = constraints.sampler().unwrap();
+        let mut rng = SkatRng::new(5);
+        for _ in 0..50 {
+            let deal = sampler.sample(&mut rng).unwrap();
+            assert_eq!(deal.player(PlayerId::ALL[0]), hand);
+            assert_eq!(
+                deal.player(PlayerId::ALL[2]).num_cards_suit(Suit::Hearts),
+                0
+            );
+            assert_eq!(deal.skat().num_jacks(), 0);
+        }
+        assert_eq!(
+            DealConstraints::new().sampler().unwrap().count(),
+            Deal::COUNT
+        );
+
+        let deal = DealConstraints::new()
+            .at_least(PLAYERS[1], CardSet::JACKS, 2)
+            .sample(&mut rng)
+            .unwrap();
+        assert!(deal.player(PlayerId::ALL[1]).num_jacks() >= 2);
+    }
+
+    #[test]
+    fn unsatisfiable() {
+        let hearts = CardSet::ALL.suit_cards(Suit::Hearts);
+        let constraints = DealConstraints::new()
+            .void(PlayerId::ALL[0], Suit::Hearts)
+            .void(PlayerId::ALL[1], Suit::Hearts)
+            .void(PlayerId::ALL[2], Suit::Hearts);
+        assert_eq!(constraints.sampler().unwrap_err(), DealError::Unsatisfiable);
+        let constraints = DealConstraints::new()
+            .holds(PLAYERS[0], hearts)
+            .at_most(PLAYERS[0], hearts, 3);
+        assert_eq!(constraints.sampler().unwrap_err(), DealError::Unsatisfiable);
+        let constraints = DealConstraints::new()
+            .at_least(Holder::Skat, CardSet::JACKS, 2)
+            .at_most(Holder::Skat, CardSet::JACKS, 1)
+            .max_attempts(10);
+        assert_eq!(
+            constraints.sample(&mut SkatRng::new(1)).unwrap_err(),
+            DealError::TooManyAttempts { attempts: 10 }
+        );
+    }
+}
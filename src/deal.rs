@@ -0,0 +1,276 @@
+//! This module contains the [Deal], the cards of the three players and the Skat.
+
+use std::fmt::{self, Display};
+
+use rand::Rng;
+
+use crate::card::{Card, ALL_CARDS};
+use crate::cardholder::{CardHolder, HAND_SIZE, SKAT_SIZE};
+use crate::cardset::CardSet;
+use crate::error::DealError;
+use crate::seat::PlayerId;
+
+/// Who holds cards after dealing: one of the three players or the Skat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Holder {
+    Player(PlayerId),
+    Skat,
+}
+
+impl Holder {
+    /// The number of cards this holder gets when dealing.
+    pub fn size(&self) -> usize {
+        match self {
+            Holder::Player(_) => HAND_SIZE,
+            Holder::Skat => SKAT_SIZE,
+        }
+    }
+}
+
+/// "Player 1" for player 0, "Skat"
+impl Display for Holder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Holder::Player(player) => write!(f, "{player}"),
+            Holder::Skat => write!(f, "Skat"),
+        }
+    }
+}
+
+/// Number of characters of a deal code, see [Deal::to_code].
+const CODE_LENGTH: usize = 10;
+
+/// n over k, only used for small values.
+pub(crate) const fn binomial(n: usize, k: usize) -> u128 {
+    if k > n {
+        return 0;
+    }
+    let mut result = 1;
+    let mut i = 0;
+    while i < k {
+        result = result * (n - i) as u128 / (i + 1) as u128;
+        i += 1;
+    }
+    result
+}
+
+/// The cards of the three players and the Skat. \
+/// A deal always holds all 32 cards, each once, 10 for each player and 2 in the Skat.
+///
+/// Each deal has a unique number between 0 and [Deal::COUNT] - 1, see [Deal::to_id].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Deal {
+    players: [CardSet; 3],
+    skat: CardSet,
+}
+
+impl Deal {
+    /// Number of distinct deals: 32! / (10!^3 * 2!) = 2,753,294,408,504,640.
+    pub const COUNT: u128 = binomial(32, 10) * binomial(22, 10) * binomial(12, 10);
+
+    /// Creates the deal and checks that each player has 10 cards, the Skat 2 and no card is given twice.
+    pub fn new(players: [CardSet; 3], skat: CardSet) -> Result<Deal, DealError> {
+        let mut dealt = CardSet::new();
+        let holders = [
+            (Holder::Player(PlayerId::ALL[0]), players[0]),
+            (Holder::Player(PlayerId::ALL[1]), players[1]),
+            (Holder::Player(PlayerId::ALL[2]), players[2]),
+            (Holder::Skat, skat),
+        ];
+        for (holder, cards) in holders {
+            if cards.len() != holder.size() {
+                return Err(DealError::WrongCardCount {
+                    holder,
+                    expected: holder.size(),
+                    found: cards.len(),
+                });
+            }
+            if let Some(card) = (dealt & cards).iter().next() {
+                return Err(DealError::DuplicateCard { card });
+            }
+            dealt |= cards;
+        }
+        Ok(Deal { players, skat })
+    }
+
+    /// Creates the deal without checks, the caller guarantees a valid deal.
+    pub(crate) fn new_unchecked(players: [CardSet; 3], skat: CardSet) -> Deal {
+        debug_assert!(Deal::new(players, skat).is_ok());
+        Deal { players, skat }
+    }
+
+    pub fn players(&self) -> &[CardSet; 3] {
+        &self.players
+    }
+
+    /// The cards of the player.
+    pub fn player(&self, player: PlayerId) -> CardSet {
+        self.players[player.index()]
+    }
+
+    pub fn skat(&self) -> CardSet {
+        self.skat
+    }
+
+    /// The cards of the holder.
+    pub fn cards(&self, holder: Holder) -> CardSet {
+        match holder {
+            Holder::Player(player) => self.players[player.index()],
+            Holder::Skat => self.skat,
+        }
+    }
+
+    /// Returns the unique number of this deal between 0 and [Deal::COUNT] - 1. \
+    /// Player 1 chooses 10 of the 32 cards, player 2 10 of the remaining 22 and player 3 10 of the
+    /// remaining 12; the Skat gets the rest. Each choice is numbered in the combinatorial number system
+    /// over the remaining cards in [Card::index] order and the numbers are combined as digits of
+    /// the bases 64,512,240, 646,646 and 66.
+    pub fn to_id(&self) -> u128 {
+        let mut remaining: Vec<Card> = ALL_CARDS.to_vec();
+        let mut id = 0;
+        for cards in self.players {
+            let base = binomial(remaining.len(), HAND_SIZE);
+            let mut rank = 0;
+            for (i, position) in remaining
+                .iter()
+                .enumerate()
+                .filter(|(_, &card)| cards.contains(card))
+                .map(|(position, _)| position)
+                .enumerate()
+            {
+                rank += binomial(position, i + 1);
+            }
+            remaining.retain(|&card| !cards.contains(card));
+            id = id * base + rank;
+        }
+        id
+    }
+
+    /// Returns the deal with the number, see [Deal::to_id].
+    pub fn from_id(id: u128) -> Result<Deal, DealError> {
+        if id >= Deal::COUNT {
+            return Err(DealError::InvalidId { id });
+        }
+        let mut remaining: Vec<Card> = ALL_CARDS.to_vec();
+        let mut players = [CardSet::new(); 3];
+        // split the id into the ranks of the players, the last player is the lowest digit
+        let mut ranks = [0; 3];
+        let mut rest = id;
+        for player in (0..3).rev() {
+            let base = binomial(ALL_CARDS.len() - HAND_SIZE * player, HAND_SIZE);
+            ranks[player] = rest % base;
+            rest /= base;
+        }
+        for (player, cards) in players.iter_mut().enumerate() {
+            let mut rank = ranks[player];
+            let mut position = remaining.len();
+            for i in (1..=HAND_SIZE).rev() {
+                // the largest position with binomial(position, i) <= rank
+                position -= 1;
+                while binomial(position, i) > rank {
+                    position -= 1;
+                }
+                rank -= binomial(position, i);
+                cards.insert(remaining[position]);
+            }
+            remaining.retain(|&card| !cards.contains(card));
+        }
+        let skat = remaining.into_iter().collect();
+        Ok(Deal::new_unchecked(players, skat))
+    }
+
+    /// Returns the deal number as 10 characters 0-9, A-Z (base 36), e.g. to share it in a bug report.
+    pub fn to_code(&self) -> String {
+        let mut id = self.to_id();
+        let mut code = vec!['0'; CODE_LENGTH];
+        for c in code.iter_mut().rev() {
+            *c = char::from_digit((id % 36) as u32, 36)
+                .unwrap()
+                .to_ascii_uppercase();
+            id /= 36;
+        }
+        code.into_iter().collect()
+    }
+
+    /// Returns the deal from its code, see [Deal::to_code]. Lower case letters are accepted.
+    pub fn from_code(code: &str) -> Result<Deal, DealError> {
+        let invalid = || DealError::InvalidCode {
+            code: code.to_string(),
+        };
+        if code.chars().count() != CODE_LENGTH {
+            return Err(invalid());
+        }
+        let mut id: u128 = 0;
+        for c in code.chars() {
+            id = id * 36 + c.to_digit(36).ok_or_else(invalid)? as u128;
+        }
+        Deal::from_id(id).map_err(|_| invalid())
+    }
+
+    /// Returns a deal chosen uniformly from all deals.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Deal {
+        Deal::from_id(rng.random_range(0..Deal::COUNT)).unwrap()
+    }
+
+    /// Creates the [CardHolder]s for player 1, 2, 3 and the Skat.
+    pub fn card_holders(&self) -> (CardHolder, CardHolder, CardHolder, CardHolder) {
+        (
+            CardHolder::new(&PlayerId::ALL[0].to_string(), self.players[0]),
+            CardHolder::new(&PlayerId::ALL[1].to_string(), self.players[1]),
+            CardHolder::new(&PlayerId::ALL[2].to_string(), self.players[2]),
+            CardHolder::new(&Holder::Skat.to_string(), self.skat),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand;
+    use crate::shuffle::SkatRng;
+
+    #[test]
+    fn validate() {
+        let players = [
+            hand!["KB", "PB", "HB", "CB", "KA", "KZ", "KK", "KD", "K9", "K8"],
+            hand!["PA", "PZ", "PK", "PD", "P9", "P8", "P7", "HA", "HZ", "HK"],
+            hand!["HD", "H9", "H8", "H7", "CA", "CZ", "CK", "CD", "C9", "C8"],
+        ];
+        let skat = hand!["K7", "C7"];
+        assert!(Deal::new(players, skat).is_ok());
+        assert_eq!(
+            Deal::new(players, hand!["K7"]),
+            Err(DealError::WrongCardCount {
+                holder: Holder::Skat,
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            Deal::new(players, hand!["K7", "KB"]),
+            Err(DealError::DuplicateCard {
+                card: Card::from_german("KB")
+            })
+        );
+    }
+
+    #[test]
+    fn id() {
+        assert_eq!(Deal::COUNT, 2_753_294_408_504_640);
+        for id in [0, 1, 4711, Deal::COUNT / 3, Deal::COUNT - 1] {
+            let deal = Deal::from_id(id).unwrap();
+            assert_eq!(deal.to_id(), id);
+            assert_eq!(Deal::from_code(&deal.to_code()), Ok(deal));
+        }
+        let mut rng = SkatRng::new(7);
+        for _ in 0..100 {
+            let deal = Deal::random(&mut rng);
+            assert_eq!(Deal::from_id(deal.to_id()), Ok(deal));
+        }
+        assert_eq!(
+            Deal::from_id(Deal::COUNT),
+            Err(DealError::InvalidId { id: Deal::COUNT })
+        );
+        assert!(Deal::from_code("ZZZZZZZZZZ").is_err());
+    }
+}
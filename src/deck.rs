@@ -1,8 +1,31 @@
 use rand::seq::SliceRandom;
+use rand::Rng;
 
 use crate::{
-    card::{Card, Rank, Suit},
+    card::{Card, ALL_CARDS},
     cardholder::CardHolder,
+    cardset::CardSet,
+    deal::{Deal, Holder},
+    error::DealError,
+    seat::PlayerId,
+    shuffle::{self, ShuffleModel, ShuffleVersion},
+};
+
+/// The holder of each card from the top of the deck: 3 to each player, 2 to the Skat,
+/// 4 to each player, 3 to each player.
+const DEAL_ORDER: [Holder; 32] = {
+    let mut order = [Holder::Skat; 32];
+    let mut i = 0;
+    while i < 32 {
+        // positions 9 and 10 are the Skat, the players take turns in the others
+        if i < 9 {
+            order[i] = Holder::Player(PlayerId::ALL[i % 3]);
+        } else if i > 10 {
+            order[i] = Holder::Player(PlayerId::ALL[(i - 11) % 3]);
+        }
+        i += 1;
+    }
+    order
 };
 
 /// The deck contains the 32 cards of the game. \
@@ -16,22 +39,9 @@ pub struct Deck {
 impl Deck {
     /// Creates a deck with 32 sorted/shuffled playing cards.
     pub fn new(shuffled: bool) -> Deck {
-        let mut cards = Vec::new();
-        for suit in &[Suit::Clubs, Suit::Spades, Suit::Hearts, Suit::Diamonds] {
-            for rank in &[
-                Rank::Seven,
-                Rank::Eight,
-                Rank::Nine,
-                Rank::Jack,
-                Rank::Queen,
-                Rank::King,
-                Rank::Ten,
-                Rank::Ace,
-            ] {
-                cards.push(Card::new(*suit, *rank));
-            }
-        }
-        let mut deck = Deck { cards };
+        let mut deck = Deck {
+            cards: ALL_CARDS.to_vec(),
+        };
         if shuffled {
             deck.shuffle();
         }
@@ -39,49 +49,159 @@ impl Deck {
         deck
     }
 
+    /// Creates a deck shuffled with the seed. The same seed always gives the same deck,
+    /// see [crate::shuffle] for the algorithm.
+    pub fn with_seed(seed: u64) -> Deck {
+        Deck::with_seed_version(seed, ShuffleVersion::V1)
+    }
+
+    /// Creates a deck shuffled with the seed using the given version of the shuffle algorithm.
+    pub fn with_seed_version(seed: u64, version: ShuffleVersion) -> Deck {
+        let mut deck = Deck::new(false);
+        shuffle::shuffle_seeded(&mut deck.cards, seed, version);
+        deck
+    }
+
+    /// Creates a deck with the cards in the given order. The last card is the top of the deck
+    /// and is dealt first, see [Deck::deal]. The deck must contain each of the 32 cards once.
+    pub fn from_cards(cards: Vec<Card>) -> Result<Deck, DealError> {
+        if cards.len() != ALL_CARDS.len() {
+            return Err(DealError::WrongDeckSize { found: cards.len() });
+        }
+        let mut seen = CardSet::new();
+        for &card in cards.iter() {
+            if !seen.insert(card) {
+                return Err(DealError::DuplicateCard { card });
+            }
+        }
+        Ok(Deck { cards })
+    }
+
     /// Returns the cards of this deck
     pub fn cards(&self) -> &[Card] {
         &self.cards
     }
 
     /// Deals the cards to the players and the Skat. After this the deck is gone as the cards are moved to the [CardHolder]s.
-    pub fn deal(&mut self) -> (CardHolder, CardHolder, CardHolder, CardHolder) {
-        let mut cards_player1 = Vec::new();
-        let mut cards_player2 = Vec::new();
-        let mut cards_player3 = Vec::new();
-        let mut cards_skat = Vec::new();
-
-        for _ in 0..3 {
-            cards_player1.push(self.cards.pop().unwrap());
-            cards_player2.push(self.cards.pop().unwrap());
-            cards_player3.push(self.cards.pop().unwrap());
-        }
-        for _ in 0..2 {
-            cards_skat.push(self.cards.pop().unwrap());
-        }
-        for _ in 0..4 {
-            cards_player1.push(self.cards.pop().unwrap());
-            cards_player2.push(self.cards.pop().unwrap());
-            cards_player3.push(self.cards.pop().unwrap());
-        }
-        for _ in 0..3 {
-            cards_player1.push(self.cards.pop().unwrap());
-            cards_player2.push(self.cards.pop().unwrap());
-            cards_player3.push(self.cards.pop().unwrap());
-        }
-
-        let mut player1 = CardHolder::new("Player 1", cards_player1);
+    pub fn deal(self) -> (CardHolder, CardHolder, CardHolder, CardHolder) {
+        let (mut player1, player2, player3, skat) = self.deal_cards().card_holders();
         player1.sort_cards();
-        let player2 = CardHolder::new("Player 2", cards_player2);
-        let player3 = CardHolder::new("Player 3", cards_player3);
-        let skat = CardHolder::new("Skat", cards_skat);
 
         (player1, player2, player3, skat)
     }
 
+    /// Deals the cards from the top of the deck: 3 to each player, 2 to the Skat, 4 to each player,
+    /// 3 to each player. After this the deck is gone.
+    pub fn deal_cards(self) -> Deal {
+        let mut players = [CardSet::new(); 3];
+        let mut skat = CardSet::new();
+
+        // a deck always holds 32 cards, see [Deck::from_cards]
+        for (holder, card) in DEAL_ORDER.into_iter().zip(self.cards.into_iter().rev()) {
+            match holder {
+                Holder::Player(player) => players[player.index()].insert(card),
+                Holder::Skat => skat.insert(card),
+            };
+        }
+
+        Deal::new_unchecked(players, skat)
+    }
+
+    /// Creates the deck which gives the deal when dealt with [Deck::deal_cards].
+    /// The cards of each holder are taken in [Card::index] order.
+    pub fn from_deal(deal: &Deal) -> Deck {
+        let mut iters = [
+            deal.player(PlayerId::ALL[0]).iter(),
+            deal.player(PlayerId::ALL[1]).iter(),
+            deal.player(PlayerId::ALL[2]).iter(),
+            deal.skat().iter(),
+        ];
+        let mut cards: Vec<Card> = DEAL_ORDER
+            .iter()
+            .filter_map(|holder| {
+                let i = match holder {
+                    Holder::Player(player) => player.index(),
+                    Holder::Skat => 3,
+                };
+                iters[i].next()
+            })
+            .collect();
+        // the first card dealt is the top of the deck, the end of the vector
+        cards.reverse();
+        Deck { cards }
+    }
+
+    /// Creates the deck by collecting the cards of the previous game: the Skat at the bottom,
+    /// then the tricks in the order they were played, the last trick on top.
+    pub fn from_tricks(tricks: &[[Card; 3]], skat: [Card; 2]) -> Result<Deck, DealError> {
+        let mut cards = skat.to_vec();
+        cards.extend(tricks.iter().flatten());
+        Deck::from_cards(cards)
+    }
+
+    /// Shuffles the cards with the model, e.g. a riffle followed by a cut:
+    /// ```
+    /// use skat_engine::deck::Deck;
+    /// use skat_engine::shuffle::{ShuffleModel, SkatRng};
+    ///
+    /// let mut rng = SkatRng::new(1);
+    /// let mut deck = Deck::new(false);
+    /// deck.apply(ShuffleModel::Riffle { passes: 4 }, &mut rng)
+    ///     .apply(ShuffleModel::RandomCut, &mut rng);
+    /// assert_eq!(deck.cards().len(), 32);
+    /// ```
+    pub fn apply<R: Rng + ?Sized>(&mut self, model: ShuffleModel, rng: &mut R) -> &mut Deck {
+        model.apply(&mut self.cards, rng);
+        self
+    }
+
     pub fn shuffle(&mut self) {
-        let mut rng = rand::rng();
-        // use rand::seq::SliceRandom;
-        self.cards.shuffle(&mut rng);
+        self.shuffle_with(&mut rand::rng());
+    }
+
+    /// Shuffles the cards with the given random number generator. \
+    /// The result depends on the shuffle of the rand crate and may change with its version,
+    /// use [Deck::with_seed] for a deal that must be reproducible.
+    pub fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.cards.shuffle(rng);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded() {
+        let deck = Deck::with_seed(42);
+        assert_eq!(deck.cards(), Deck::with_seed(42).cards());
+        assert_ne!(deck.cards(), Deck::with_seed(43).cards());
+        // the seeded shuffle must never change
+        let first: Vec<usize> = deck.cards()[..8].iter().map(|c| c.index()).collect();
+        assert_eq!(first, vec![22, 6, 2, 12, 16, 21, 13, 19]);
+    }
+
+    #[test]
+    fn from_cards() {
+        let mut cards = ALL_CARDS.to_vec();
+        let deal = Deck::from_cards(cards.clone()).unwrap().deal_cards();
+        // the top card is dealt first to player 1, the 10th and 11th card from the top go to the Skat
+        assert!(deal.player(PlayerId::ALL[0]).contains(ALL_CARDS[31]));
+        assert_eq!(
+            deal.skat(),
+            CardSet::from_iter([ALL_CARDS[21], ALL_CARDS[22]])
+        );
+
+        assert_eq!(Deck::from_deal(&deal).deal_cards(), deal);
+
+        let tricks: Vec<[Card; 3]> = cards[2..].chunks(3).map(|t| [t[0], t[1], t[2]]).collect();
+        let deck = Deck::from_tricks(&tricks, [cards[0], cards[1]]).unwrap();
+        assert_eq!(deck.cards(), &cards[..]);
+
+        cards[0] = cards[1];
+        assert_eq!(
+            Deck::from_cards(cards).unwrap_err(),
+            DealError::DuplicateCard { card: ALL_CARDS[1] }
+        );
     }
 }
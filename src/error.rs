@@ -0,0 +1,225 @@
+//! This module contains the errors of the crate.
+
+use std::fmt::{self, Display};
+
+use crate::bidding::{BidAction, BidValue};
+use crate::card::Card;
+use crate::cardset::CardSet;
+use crate::deal::Holder;
+use crate::seat::PlayerId;
+
+/// Error when reading cards, suits, ranks or hands from user input. \
+/// The position is the index of the offending token, e.g. the 3rd card of a hand is position 2.
+/// When a single card is parsed, the position is 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The symbol is not a known suit.
+    UnknownSuit { symbol: char, position: usize },
+    /// The suit symbol means different suits in the German and English notation (C)
+    /// and the notation can not be detected from the other cards.
+    AmbiguousSuit { symbol: char, position: usize },
+    /// The symbol is not a known rank.
+    UnknownRank { symbol: char, position: usize },
+    /// The number is not a valid suit, rank or card index.
+    OutOfRange { value: usize, position: usize },
+    /// A card needs a suit and a rank symbol.
+    WrongLength { length: usize, position: usize },
+    /// The card is given more than once.
+    DuplicateCard { card: Card, position: usize },
+    /// The hand does not have the required number of cards.
+    WrongHandSize { expected: usize, found: usize },
+}
+
+impl ParseError {
+    /// The index of the offending token, None if the error concerns the whole hand.
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            ParseError::UnknownSuit { position, .. }
+            | ParseError::AmbiguousSuit { position, .. }
+            | ParseError::UnknownRank { position, .. }
+            | ParseError::OutOfRange { position, .. }
+            | ParseError::WrongLength { position, .. }
+            | ParseError::DuplicateCard { position, .. } => Some(*position),
+            ParseError::WrongHandSize { .. } => None,
+        }
+    }
+
+    /// Returns the same error for the token at the given position.
+    pub(crate) fn at(mut self, new_position: usize) -> ParseError {
+        match &mut self {
+            ParseError::UnknownSuit { position, .. }
+            | ParseError::AmbiguousSuit { position, .. }
+            | ParseError::UnknownRank { position, .. }
+            | ParseError::OutOfRange { position, .. }
+            | ParseError::WrongLength { position, .. }
+            | ParseError::DuplicateCard { position, .. } => *position = new_position,
+            ParseError::WrongHandSize { .. } => {}
+        }
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnknownSuit { symbol, position } => write!(
+                f,
+                "Unknown suit '{symbol}' at position {position}: use K = Club, P = Spades, H = Hearts, C = Diamonds"
+            ),
+            ParseError::AmbiguousSuit { symbol, position } => write!(
+                f,
+                "Ambiguous suit '{symbol}' at position {position}: is Diamonds in German and Clubs in English notation"
+            ),
+            ParseError::UnknownRank { symbol, position } => write!(
+                f,
+                "Unknown rank '{symbol}' at position {position}: use 7,8,9, Z/T = ten, B/J = Jack, D/Q = Queen, K = King, A = Ace"
+            ),
+            ParseError::OutOfRange { value, position } => {
+                write!(f, "Value {value} out of range at position {position}")
+            }
+            ParseError::WrongLength { length, position } => write!(
+                f,
+                "Card at position {position} has {length} symbols, need two symbols"
+            ),
+            ParseError::DuplicateCard { card, position } => {
+                write!(f, "Card {card} at position {position} is given twice")
+            }
+            ParseError::WrongHandSize { expected, found } => {
+                write!(f, "Need {expected} cards, found {found}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Error when the cards of a deal or deck given from outside are not a valid set of cards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DealError {
+    /// A player does not hold 10 cards or the Skat does not hold 2.
+    WrongCardCount {
+        holder: Holder,
+        expected: usize,
+        found: usize,
+    },
+    /// The card is given more than once.
+    DuplicateCard { card: Card },
+    /// A deck needs 32 cards.
+    WrongDeckSize { found: usize },
+    /// The deal number is not below [crate::deal::Deal::COUNT].
+    InvalidId { id: u128 },
+    /// The deal code is not 10 characters 0-9, A-Z of a valid deal number.
+    InvalidCode { code: String },
+    /// No deal fulfills the constraints, see [crate::constraints::DealConstraints].
+    Unsatisfiable,
+    /// No deal fulfilling the constraints was found in the given number of attempts.
+    TooManyAttempts { attempts: usize },
+}
+
+impl Display for DealError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DealError::WrongCardCount {
+                holder,
+                expected,
+                found,
+            } => write!(f, "{holder} needs {expected} cards, found {found}"),
+            DealError::DuplicateCard { card } => write!(f, "Card {card} is given twice"),
+            DealError::WrongDeckSize { found } => {
+                write!(f, "A deck needs 32 cards, found {found}")
+            }
+            DealError::InvalidId { id } => write!(f, "Deal number {id} is too large"),
+            DealError::InvalidCode { code } => write!(f, "Deal code {code} is not valid"),
+            DealError::Unsatisfiable => write!(f, "No deal fulfills the constraints"),
+            DealError::TooManyAttempts { attempts } => {
+                write!(
+                    f,
+                    "No deal fulfilling the constraints found in {attempts} attempts"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for DealError {}
+
+/// Error when a game is built or changed with cards or players given from outside.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameError {
+    /// The cards are not a valid deal.
+    Deal(DealError),
+    /// There are only the players 0..3.
+    InvalidPlayer { index: usize },
+    /// The player does not hold the card to play.
+    CardNotHeld { player: PlayerId, card: Card },
+    /// The hands, the Skat and the tricks do not hold each of the 32 cards once.
+    CardsNotConserved {
+        missing: CardSet,
+        duplicate: CardSet,
+    },
+}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::Deal(error) => write!(f, "{error}"),
+            GameError::InvalidPlayer { index } => {
+                write!(f, "Player {index} does not exist, use 0..3")
+            }
+            GameError::CardNotHeld { player, card } => {
+                write!(f, "{player} does not hold {card}")
+            }
+            GameError::CardsNotConserved { missing, duplicate } => {
+                write!(f, "Cards missing: [{missing}], given twice: [{duplicate}]")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GameError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GameError::Deal(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<DealError> for GameError {
+    fn from(error: DealError) -> Self {
+        GameError::Deal(error)
+    }
+}
+
+/// Error when a player acts against the rules of the bidding, see [crate::bidding::Bidding].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BiddingError {
+    /// Another player has to act.
+    NotYourTurn { expected: PlayerId, found: PlayerId },
+    /// The action is not allowed now, e.g. the listening player bids.
+    IllegalAction { action: BidAction },
+    /// The value must be at least the next value after the highest bid.
+    BidTooLow { value: BidValue, minimum: BidValue },
+    /// The value is not the value of a game.
+    InvalidValue { value: u16 },
+    /// The bidding is over.
+    Finished,
+}
+
+impl Display for BiddingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BiddingError::NotYourTurn { expected, found } => {
+                write!(f, "{found} acted, but it is the turn of {expected}")
+            }
+            BiddingError::IllegalAction { action } => write!(f, "\"{action}\" is not allowed now"),
+            BiddingError::BidTooLow { value, minimum } => {
+                write!(f, "Bid {value} is too low, at least {minimum} needed")
+            }
+            BiddingError::InvalidValue { value } => write!(f, "{value} is not a game value"),
+            BiddingError::Finished => write!(f, "The bidding is over"),
+        }
+    }
+}
+
+impl std::error::Error for BiddingError {}
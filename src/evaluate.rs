@@ -0,0 +1,269 @@
+//! This module contains the evaluation of a hand for the bidding, see [NullEvaluation] and
+//! [GrandEvaluation].
+
+use crate::card::{Card, Rank, Suit, ALL_SUITS};
+use crate::cardset::CardSet;
+use crate::game::GameType;
+use crate::matadors::Matadors;
+use crate::value::Contract;
+
+/// Gaps the opponents do not see which are still accepted for Null and Null Hand.
+const NULL_HIDDEN_GAPS: u8 = 2;
+
+/// Grand needs at least this many jacks.
+const GRAND_MIN_JACKS: usize = 2;
+
+/// Grand needs at least 6 expected tricks, counted in half tricks.
+const GRAND_MIN_HALF_TRICKS: u8 = 12;
+
+/// A suit with this many cards and at least Ace and 10 runs through.
+const GRAND_LONG_SUIT: usize = 5;
+
+/// The ranks of a suit in Grand from the highest down.
+const GRAND_SUIT_ORDER: [Rank; 7] = [
+    Rank::Ace,
+    Rank::Ten,
+    Rank::King,
+    Rank::Queen,
+    Rank::Nine,
+    Rank::Eight,
+    Rank::Seven,
+];
+
+/// The safety of a hand for Null by the gap rule: the lowest card of a suit has to be the 7,
+/// the second lowest at most the 9, the third at most the Jack and so on, each card at most two
+/// ranks above the one below. Such a suit can always be ducked when the opponents lead it. \
+/// A card above this is a gap, counted by the number of ranks it is too high:
+/// 7-9-J is safe, a singleton 8 has one gap, a singleton Ace seven.
+/// ```
+/// use skat_engine::evaluate::NullEvaluation;
+/// use skat_engine::card::Suit;
+///
+/// let hand = skat_engine::hand!["K7", "K9", "KB", "P7", "P8", "PZ", "H8", "C7", "C8", "C9"];
+/// let null = NullEvaluation::new(hand);
+/// assert!(null.is_safe(Suit::Clubs));
+/// assert_eq!(null.suit_gaps(Suit::Hearts), 1);
+/// assert_eq!(null.value(), Some(46));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NullEvaluation {
+    /// Gaps of Clubs, Spades, Hearts, Diamonds.
+    gaps: [u8; 4],
+    /// Gaps after discarding the worst card, expecting the Skat to be no worse.
+    gaps_with_skat: u8,
+}
+
+impl NullEvaluation {
+    pub fn new(cards: CardSet) -> NullEvaluation {
+        let gaps = ALL_SUITS.map(|suit| suit_gaps(cards & CardSet::of_suit(suit)));
+        let gaps_with_skat = cards
+            .subsets(1)
+            .map(|discard| total_gaps(cards.difference(discard)))
+            .min()
+            .unwrap_or_default();
+        NullEvaluation {
+            gaps,
+            gaps_with_skat,
+        }
+    }
+
+    /// The gaps of the suit, 0 if it is safe or void.
+    pub fn suit_gaps(&self, suit: Suit) -> u8 {
+        self.gaps[suit as usize]
+    }
+
+    pub fn is_safe(&self, suit: Suit) -> bool {
+        self.suit_gaps(suit) == 0
+    }
+
+    /// The gaps of all suits.
+    pub fn gaps(&self) -> u8 {
+        self.gaps.iter().sum()
+    }
+
+    /// The highest Null which can be played: Null Ouvert Hand if all suits are safe,
+    /// Null Ouvert if they are after discarding, Null Hand or Null with few gaps left.
+    /// None if the hand is too risky for Null.
+    pub fn contract(&self) -> Option<Contract> {
+        let null = Contract::new(GameType::Null);
+        if self.gaps() == 0 {
+            Some(null.ouvert().hand())
+        } else if self.gaps_with_skat == 0 {
+            Some(null.ouvert())
+        } else if self.gaps() <= NULL_HIDDEN_GAPS {
+            Some(null.hand())
+        } else if self.gaps_with_skat <= NULL_HIDDEN_GAPS {
+            Some(null)
+        } else {
+            None
+        }
+    }
+
+    /// The value of [NullEvaluation::contract], the limit for the bidding.
+    pub fn value(&self) -> Option<u16> {
+        self.contract()?.base_value()
+    }
+}
+
+/// The strength of a hand for Grand, estimated in expected tricks:
+/// - the unbroken jacks from the Jack of Clubs down take a trick each, the other jacks half a trick
+/// - standing aces and tens (Ace, Ace-10, Ace-10-King, ...) take a trick each,
+///   a long suit with Ace and 10 runs through with all its cards
+/// - a ten guarded by another card without the Ace takes half a trick,
+///   a blank ten is likely lost and costs half a trick
+/// ```
+/// use skat_engine::evaluate::GrandEvaluation;
+///
+/// let hand = skat_engine::hand!["KB", "PB", "HB", "KA", "KZ", "PA", "PZ", "PK", "HA", "CZ"];
+/// let grand = GrandEvaluation::new(hand);
+/// assert_eq!(grand.expected_tricks(), 8.5);
+/// assert_eq!(grand.blank_tens(), 1);
+/// // with 3 plays 4
+/// assert_eq!(grand.value(), Some(96));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GrandEvaluation {
+    jacks: usize,
+    standing: u8,
+    blank_tens: u8,
+    half_tricks: u8,
+    matadors: Matadors,
+}
+
+impl GrandEvaluation {
+    pub fn new(cards: CardSet) -> GrandEvaluation {
+        let jacks = ALL_SUITS.map(|suit| cards.contains(Card::new(suit, Rank::Jack)));
+        let top_jacks = jacks.iter().take_while(|&&held| held).count();
+        let jack_count = cards.num_jacks();
+        let mut half_tricks = (2 * top_jacks + (jack_count - top_jacks)) as u8;
+
+        let mut standing = 0;
+        let mut blank_tens = 0;
+        for suit in ALL_SUITS {
+            let suit_cards = cards.suit_cards(suit);
+            let run = GRAND_SUIT_ORDER
+                .iter()
+                .take_while(|&&rank| suit_cards.contains(Card::new(suit, rank)))
+                .count() as u8;
+            standing += run;
+            half_tricks += if run >= 2 && suit_cards.len() >= GRAND_LONG_SUIT {
+                2 * suit_cards.len() as u8
+            } else {
+                2 * run
+            };
+            if run == 0 && suit_cards.contains(Card::new(suit, Rank::Ten)) {
+                if suit_cards.len() == 1 {
+                    blank_tens += 1;
+                } else {
+                    half_tricks += 1;
+                }
+            }
+        }
+        GrandEvaluation {
+            jacks: jack_count,
+            standing,
+            blank_tens,
+            half_tricks: half_tricks.saturating_sub(blank_tens),
+            matadors: Matadors::new(cards, &GameType::Grand),
+        }
+    }
+
+    pub fn jacks(&self) -> usize {
+        self.jacks
+    }
+
+    /// The standing aces and tens and the cards following them, e.g. 3 for Ace-10-King.
+    pub fn standing(&self) -> u8 {
+        self.standing
+    }
+
+    /// Tens without another card of the suit.
+    pub fn blank_tens(&self) -> u8 {
+        self.blank_tens
+    }
+
+    pub fn expected_tricks(&self) -> f64 {
+        f64::from(self.half_tricks) / 2.0
+    }
+
+    pub fn matadors(&self) -> Matadors {
+        self.matadors
+    }
+
+    /// Grand can be played with enough jacks and expected tricks.
+    pub fn is_playable(&self) -> bool {
+        self.jacks >= GRAND_MIN_JACKS && self.half_tricks >= GRAND_MIN_HALF_TRICKS
+    }
+
+    /// The value of Grand, 24 times the matadors multiplier, the limit for the bidding.
+    /// None if Grand can not be played.
+    pub fn value(&self) -> Option<u16> {
+        let base = Contract::new(GameType::Grand).base_value()?;
+        self.is_playable()
+            .then(|| base * self.matadors.multiplier())
+    }
+}
+
+fn suit_gaps(suit_cards: CardSet) -> u8 {
+    let mut orders: Vec<u8> = suit_cards
+        .iter()
+        .map(|card| card.rank().order(&GameType::Null))
+        .collect();
+    orders.sort_unstable();
+    orders
+        .into_iter()
+        .enumerate()
+        .map(|(i, order)| order.saturating_sub(2 * i as u8))
+        .sum()
+}
+
+fn total_gaps(cards: CardSet) -> u8 {
+    ALL_SUITS
+        .iter()
+        .map(|&suit| suit_gaps(cards & CardSet::of_suit(suit)))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand;
+
+    #[test]
+    fn null_gaps() {
+        assert_eq!(suit_gaps(hand!["K7", "K9", "KB"]), 0);
+        assert_eq!(suit_gaps(hand!["K8"]), 1);
+        assert_eq!(suit_gaps(hand!["K7", "KZ"]), 1);
+        assert_eq!(suit_gaps(CardSet::EMPTY), 0);
+
+        let safe = hand!["K7", "K9", "KB", "P7", "P8", "P9", "H7", "H9", "C7", "C8"];
+        let null = NullEvaluation::new(safe);
+        assert_eq!(null.gaps(), 0);
+        assert_eq!(null.value(), Some(59));
+
+        let two_gaps = hand!["K8", "K9", "KB", "P7", "P8", "PZ", "H8", "C7", "C8", "C9"];
+        let null = NullEvaluation::new(two_gaps);
+        assert_eq!(null.contract(), Some(Contract::new(GameType::Null).hand()));
+
+        let aces = hand!["KA", "PA", "HA", "CA", "KB", "PB", "HB", "CB", "KK", "PK"];
+        assert_eq!(NullEvaluation::new(aces).contract(), None);
+    }
+
+    #[test]
+    fn grand() {
+        let four_jacks = hand!["KB", "PB", "HB", "CB", "KA", "KZ", "PA", "PZ", "HA", "CK"];
+        let grand = GrandEvaluation::new(four_jacks);
+        assert_eq!(grand.standing(), 5);
+        assert_eq!(grand.expected_tricks(), 9.0);
+        assert_eq!(grand.value(), Some(120));
+
+        // a long suit runs through, but without jacks there is no Grand
+        let long_suit = hand!["KA", "KZ", "KK", "KD", "K9", "K8", "K7", "PA", "HA", "CA"];
+        let grand = GrandEvaluation::new(long_suit);
+        assert_eq!(grand.expected_tricks(), 10.0);
+        assert_eq!(grand.value(), None);
+
+        let weak = hand!["HB", "CB", "KZ", "PK", "PD", "HA", "H9", "C8", "C7", "K7"];
+        assert!(!GrandEvaluation::new(weak).is_playable());
+    }
+}
@@ -1,11 +1,20 @@
 use std::fmt;
 use std::fmt::Display;
 
+use crate::bidding::{Bidding, BiddingResult};
+use crate::card::Card;
 use crate::cardholder::CardHolder;
+use crate::cardset::CardSet;
+use crate::deal::Deal;
 use crate::deck::Deck;
+use crate::error::GameError;
+use crate::notation::{Notation, NotationDisplay};
+use crate::seat::{PlayerId, Seat};
+use crate::unknown::UnknownCards;
 
 /// The Skat-game a player announced.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GameType {
     Grand,
     Clubs,
@@ -26,95 +35,211 @@ impl GameType {
             crate::card::Suit::Diamonds => GameType::Diamonds,
         }
     }
+
+    /// The suit which is trump besides the jacks, None for Grand, Null, Ramsch.
+    pub fn trump_suit(&self) -> Option<crate::card::Suit> {
+        match self {
+            GameType::Clubs => Some(crate::card::Suit::Clubs),
+            GameType::Spades => Some(crate::card::Suit::Spades),
+            GameType::Hearts => Some(crate::card::Suit::Hearts),
+            GameType::Diamonds => Some(crate::card::Suit::Diamonds),
+            _ => None,
+        }
+    }
 }
 
-impl Display for GameType {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+/// Suit games are named by the suit name, e.g. "Kreuz" in German, "Clubs" in English, "♣" in Unicode.
+impl NotationDisplay for GameType {
+    fn fmt_notation(&self, f: &mut fmt::Formatter<'_>, notation: Notation) -> fmt::Result {
+        let german = matches!(
+            notation,
+            Notation::German | Notation::GermanNames | Notation::GermanDeck
+        );
+        if let Some(suit) = self.trump_suit() {
+            let suit_notation = match notation {
+                Notation::German => Notation::GermanNames,
+                Notation::English => Notation::EnglishNames,
+                _ => notation,
+            };
+            return write!(f, "{}", suit.name(suit_notation));
+        }
         match self {
             GameType::Grand => write!(f, "Grand"),
-            GameType::Clubs => write!(f, "Clubs"),
-            GameType::Spades => write!(f, "Spades"),
-            GameType::Hearts => write!(f, "Hearts"),
-            GameType::Diamonds => write!(f, "Diamonds"),
             GameType::Null => write!(f, "Null"),
             GameType::Ramsch => write!(f, "Ramsch"),
-            GameType::None => write!(f, "None"),
+            GameType::None if german => write!(f, "Kein Spiel"),
+            _ => write!(f, "None"),
         }
     }
 }
 
+impl Display for GameType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_notation(f, Notation::EnglishNames)
+    }
+}
+
+/// One trick, the cards in the order they were played starting with the leader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Trick {
+    pub leader: PlayerId,
+    pub cards: [Card; 3],
+}
+
 /// This is the structure for one game with 3 players and the Skat.
 /// A new game creates a game and deals the cards to the players. \
+/// The players are numbered by [PlayerId], their [Seat] follows from the player in Vorhand.
 // TODO Further functionality like reizen needs to be extended.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
     player: [CardHolder; 3],
     skat: CardHolder,
-    pub vorhand: usize,
-    // pub mittelhand: usize,
-    // pub hinterhand: usize,
-    pub player_playing: Option<usize>,
+    vorhand: PlayerId,
+    pub player_playing: Option<PlayerId>,
+    tricks: Vec<Trick>,
 }
 
 impl Game {
     /// Creates a new game. \
     /// A card deck is created, shuffled and the cards are dealt to the players.
-    pub fn new(vorhand: usize) -> Self {
-        let mut deck = Deck::new(true);
-        let (player1, player2, player3, skat) = deck.deal();
-        // let (mittelhand, hinterhand) = match vorhand {
-        //     0 => (1, 2),
-        //     1 => (2, 0),
-        //     2 => (0, 1),
-        //     _ => panic!("Invalid player number"),
-        // };
+    pub fn new(vorhand: PlayerId) -> Self {
+        Self::from_deck(vorhand, Deck::new(true))
+    }
+
+    /// Creates a new game with the cards shuffled from the seed.
+    /// The same seed always gives the same game, see [Deck::with_seed].
+    pub fn with_seed(vorhand: PlayerId, seed: u64) -> Self {
+        Self::from_deck(vorhand, Deck::with_seed(seed))
+    }
+
+    /// Creates a game from the deck in its given order, which is dealt as in [Deck::deal_cards]
+    /// starting with Vorhand. Use [Deck::from_cards] to create the deck.
+    pub fn from_deck(vorhand: PlayerId, deck: Deck) -> Self {
+        Self::from_deal(vorhand, Self::deal_from_deck(vorhand, deck))
+    }
+
+    /// The deal of [Game::from_deck]: the first hand dealt goes to Vorhand.
+    pub(crate) fn deal_from_deck(vorhand: PlayerId, deck: Deck) -> Deal {
+        let dealt = deck.deal_cards();
+        let players =
+            PlayerId::ALL.map(|p| dealt.player(PlayerId::ALL[Seat::of(p, vorhand) as usize]));
+        Deal::new_unchecked(players, dealt.skat())
+    }
+
+    /// Creates a game with the given cards of the players and the Skat,
+    /// e.g. to replay a game. Use [Deal::new] to create the deal.
+    pub fn from_deal(vorhand: PlayerId, deal: Deal) -> Self {
+        let (player1, player2, player3, skat) = deal.card_holders();
         Self {
             player: [player1, player2, player3],
             skat,
             vorhand,
-            // mittelhand,
-            // hinterhand,
             player_playing: Option::None,
+            tricks: Vec::new(),
         }
     }
 
-    // pub fn new_defined(
-    //     player1: CardHolder,
-    //     player2: CardHolder,
-    //     player3: CardHolder,
-    //     skat: CardHolder,
-    // ) -> Self {
-    //     Self {
-    //         player1,
-    //         player2,
-    //         player3,
-    //         skat,
-    //     }
-    // }
+    /// Creates a game from cards given from outside, e.g. user input.
+    /// # Errors
+    /// [GameError::InvalidPlayer] if vorhand is not 0..3, [GameError::Deal] if the cards are not a valid deal.
+    pub fn from_cards(
+        vorhand: usize,
+        players: [CardSet; 3],
+        skat: CardSet,
+    ) -> Result<Self, GameError> {
+        let vorhand = PlayerId::try_from(vorhand)?;
+        Ok(Self::from_deal(vorhand, Deal::new(players, skat)?))
+    }
 
-    /// Return the player who did the highest reizen
-    // TODO actual steps and reizen value
-    pub fn reizen(&mut self) -> Option<usize> {
-        let (mittelhand, hinterhand) = match self.vorhand {
-            0 => (1, 2),
-            1 => (2, 0),
-            2 => (0, 1),
-            _ => panic!("Invalid player number"),
-        };
+    /// Checks that the hands, the Skat and the tricks hold each of the 32 cards once,
+    /// e.g. after deserializing a game. The players need no check, a [PlayerId] is always valid.
+    pub fn validate(&self) -> Result<(), GameError> {
+        let mut seen = CardSet::new();
+        let mut duplicate = CardSet::new();
+        let held = self
+            .player
+            .iter()
+            .chain([&self.skat])
+            .flat_map(|h| h.cards());
+        let played = self.tricks.iter().flat_map(|t| t.cards);
+        for card in held.chain(played) {
+            if !seen.insert(card) {
+                duplicate.insert(card);
+            }
+        }
+        let missing = !seen;
+        if missing.is_empty() && duplicate.is_empty() {
+            Ok(())
+        } else {
+            Err(GameError::CardsNotConserved { missing, duplicate })
+        }
+    }
 
-        let mut player = self.vorhand;
-        if self.player[mittelhand].reizen_max() > self.player[self.vorhand].reizen_max() {
-            player = mittelhand;
+    /// Plays the trick: the leader and the next players play the cards in this order.
+    /// # Errors
+    /// [GameError::CardNotHeld] if a player does not hold the card, then nothing is changed.
+    pub fn record_trick(&mut self, leader: PlayerId, cards: [Card; 3]) -> Result<(), GameError> {
+        let players = [leader, leader.next(), leader.next().next()];
+        let mut remaining = players.map(|p| self.player_id(p).cards());
+        for (i, (&player, &card)) in players.iter().zip(cards.iter()).enumerate() {
+            if !remaining[i].remove(card) {
+                return Err(GameError::CardNotHeld { player, card });
+            }
         }
-        if self.player[hinterhand].reizen_max() > self.player[player].reizen_max() {
-            player = hinterhand;
+        for (player, card) in players.into_iter().zip(cards) {
+            self.player_id_as_mut(player).remove_card(card);
         }
-        if self.player[player].reizen_max() == 0 {
-            return None;
+        self.tricks.push(Trick { leader, cards });
+        Ok(())
+    }
+
+    /// The tricks played so far.
+    pub fn tricks(&self) -> &[Trick] {
+        &self.tricks
+    }
+
+    /// Names the players, e.g. by the people at the table.
+    pub fn set_names(&mut self, names: [&str; 3]) {
+        for (player, name) in self.player.iter_mut().zip(names) {
+            player.set_name(name);
+        }
+    }
+
+    pub fn vorhand(&self) -> PlayerId {
+        self.vorhand
+    }
+
+    /// The seat of the player in this game.
+    pub fn seat_of(&self, player: PlayerId) -> Seat {
+        Seat::of(player, self.vorhand)
+    }
+
+    /// The player in the seat, e.g. `game.player_at(Seat::Mittelhand)`.
+    pub fn player_at(&self, seat: Seat) -> &CardHolder {
+        self.player_id(seat.player(self.vorhand))
+    }
+
+    /// Runs the bidding, each player bids and holds up to its [CardHolder::reizen_max].
+    /// # Returns
+    /// The declarer, whose [CardHolder::reizen_current] is the final bid, None if all passed.
+    pub fn reizen(&mut self) -> Option<PlayerId> {
+        let mut bidding = Bidding::new(self.vorhand);
+        while let Some(player) = bidding.turn() {
+            let action = bidding.action_up_to(self.player[player.index()].reizen_max());
+            bidding
+                .act(player, action)
+                .expect("the action is legal by construction");
+        }
+        match bidding.result()? {
+            BiddingResult::Declarer { player, value } => {
+                self.player_id_as_mut(player).reizen_current = Some(value);
+                self.player_playing = Some(player);
+                Some(player)
+            }
+            BiddingResult::AllPassed => None,
         }
-        self.player[player].reizen_current = self.player[player].reizen_max();
-        self.player_playing = Some(player);
-        Some(player)
     }
 
     // identify the player with the most jacks
@@ -146,28 +271,118 @@ impl Game {
         (without_skat, with_skat)
     }
 
+    /// The probability from the view of the player that the Skat completes the 4 jacks,
+    /// counted exactly over all possible Skats, see [UnknownCards].
+    /// # Errors
+    /// If the player does not hold 10 cards, e.g. after a trick was played.
+    pub fn chance_4_jacks_with_skat(&self, id: PlayerId) -> Result<f64, GameError> {
+        let jacks = self.player_id(id).num_jacks();
+        let unknown = UnknownCards::new(id, self.player_id(id))?;
+        Ok(unknown.skat_probability(|skat| jacks + skat.num_jacks() == 4))
+    }
+
     /// Sorts the cards for each card holder for better display.
     pub fn sort_cards(&mut self) {
+        self.sort_cards_for(&GameType::None);
+    }
+
+    /// Sorts the cards for each card holder as they rank in the given game.
+    pub fn sort_cards_for(&mut self, game_type: &GameType) {
         for i in 0..3 {
-            self.player[i].sort_cards();
+            self.player[i].sort_cards_for(game_type);
         }
-        self.skat.sort_cards();
+        self.skat.sort_cards_for(game_type);
     }
 
     pub fn player(&self) -> &[CardHolder; 3] {
         &self.player
     }
 
-    pub fn player_id(&self, id: usize) -> &CardHolder {
-        &self.player[id]
+    pub fn player_id(&self, id: PlayerId) -> &CardHolder {
+        &self.player[id.index()]
     }
 
     // TODO maybe move reizen
-    pub fn player_id_as_mut(&mut self, id: usize) -> &mut CardHolder {
-        &mut self.player[id]
+    pub fn player_id_as_mut(&mut self, id: PlayerId) -> &mut CardHolder {
+        &mut self.player[id.index()]
     }
 
     pub fn skat(&self) -> &CardHolder {
         &self.skat
     }
 }
+
+/// One line for each player and the Skat.
+impl NotationDisplay for Game {
+    fn fmt_notation(&self, f: &mut fmt::Formatter<'_>, notation: Notation) -> fmt::Result {
+        for player in self.player.iter() {
+            writeln!(f, "{}", player.with_notation(notation))?;
+        }
+        write!(f, "{}", self.skat.with_notation(notation))
+    }
+}
+
+impl Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_notation(f, Notation::German)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bidding::BidValue;
+    use crate::hand;
+
+    #[test]
+    fn from_deal() {
+        let deal = Deal::new(
+            [
+                hand!["KB", "PB", "HB", "PA", "PZ", "PK", "PD", "P9", "P8", "P7"],
+                hand!["CB", "HA", "HZ", "HK", "HD", "H9", "H8", "H7", "CA", "CZ"],
+                hand!["KA", "KZ", "KK", "KD", "K9", "K8", "K7", "CK", "CD", "C9"],
+            ],
+            hand!["C8", "C7"],
+        )
+        .unwrap();
+        let declarer = PlayerId::ALL[0];
+        let mut game = Game::from_deal(PlayerId::ALL[1], deal);
+        assert_eq!(game.seat_of(declarer), Seat::Hinterhand);
+        assert_eq!(game.play_with_4_jacks(), (false, false));
+        let chance = game.chance_4_jacks_with_skat(declarer).unwrap();
+        assert!((chance - 21.0 / 231.0).abs() < 1e-12);
+        assert_eq!(game.chance_4_jacks_with_skat(PlayerId::ALL[2]), Ok(0.0));
+        // Grand with 3 plays 4 beats Clubs without 4 plays 5, held up to 60
+        assert_eq!(game.reizen(), Some(declarer));
+        assert_eq!(game.player_id(declarer).reizen_current, BidValue::new(63));
+        assert_eq!(game.player_id(declarer).game_type(), &GameType::Grand);
+    }
+
+    #[test]
+    fn validate() {
+        let mut game = Game::with_seed(PlayerId::ALL[0], 1);
+        assert_eq!(game.validate(), Ok(()));
+        let leader = PlayerId::ALL[0];
+        let cards = [leader, leader.next(), leader.next().next()]
+            .map(|p| game.player_id(p).cards().iter().next().unwrap());
+        assert_eq!(
+            game.record_trick(leader.next(), cards),
+            Err(GameError::CardNotHeld {
+                player: leader.next(),
+                card: cards[0]
+            })
+        );
+        assert_eq!(game.record_trick(leader, cards), Ok(()));
+        assert_eq!(game.player_id(leader).cards().len(), 9);
+        assert_eq!(game.validate(), Ok(()));
+
+        assert!(matches!(
+            Game::from_cards(3, [CardSet::new(); 3], CardSet::new()),
+            Err(GameError::InvalidPlayer { index: 3 })
+        ));
+        assert!(matches!(
+            Game::from_cards(0, [CardSet::new(); 3], CardSet::new()),
+            Err(GameError::Deal(_))
+        ));
+    }
+}
@@ -0,0 +1,116 @@
+//! This module contains the [GameGenerator], a reproducible stream of random games for simulations.
+
+use crate::deal::Deal;
+use crate::deck::Deck;
+use crate::game::Game;
+use crate::seat::PlayerId;
+use crate::shuffle::SkatRng;
+
+/// An endless iterator of random games from a seed, e.g. `GameGenerator::new(42).take(1000)`. \
+/// Game i is dealt from [Deck::with_seed] with the i-th number of [SkatRng] from the seed, so each
+/// game can be created on its own. This allows splitting the stream into substreams for several
+/// threads, which together give the same games as the whole stream, see [GameGenerator::substream].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameGenerator {
+    seed: u64,
+    /// Index of the next game in the whole stream.
+    index: u64,
+    step: u64,
+    rotate: bool,
+}
+
+impl GameGenerator {
+    /// All games have player 1 in Vorhand, see [GameGenerator::with_rotation].
+    pub fn new(seed: u64) -> GameGenerator {
+        GameGenerator {
+            seed,
+            index: 0,
+            step: 1,
+            rotate: false,
+        }
+    }
+
+    /// The dealer moves on after each game, game i has player i % 3 in Vorhand.
+    pub fn with_rotation(mut self) -> GameGenerator {
+        self.rotate = true;
+        self
+    }
+
+    /// The k-th of n substreams: the games k, k + n, k + 2n, ... of this stream.
+    /// The n substreams together give the same games as this stream.
+    /// # Panics
+    /// If n is 0.
+    pub fn substream(&self, k: u64, n: u64) -> GameGenerator {
+        assert!(n > 0, "at least one substream needed");
+        GameGenerator {
+            index: self.index + k * self.step,
+            step: self.step * n,
+            ..self.clone()
+        }
+    }
+
+    /// Index of the next game in the whole stream.
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// The deals of the games with the same hands of the players, lighter than creating the games.
+    pub fn deals(mut self) -> impl Iterator<Item = Deal> {
+        std::iter::from_fn(move || {
+            let (deck, vorhand) = self.next_deck();
+            Some(Game::deal_from_deck(vorhand, deck))
+        })
+    }
+
+    fn next_deck(&mut self) -> (Deck, PlayerId) {
+        let deck = Deck::with_seed(SkatRng::nth_from(self.seed, self.index));
+        let vorhand = match self.rotate {
+            true => PlayerId::ALL[(self.index % 3) as usize],
+            false => PlayerId::ALL[0],
+        };
+        self.index += self.step;
+        (deck, vorhand)
+    }
+}
+
+impl Iterator for GameGenerator {
+    type Item = Game;
+
+    fn next(&mut self) -> Option<Game> {
+        let (deck, vorhand) = self.next_deck();
+        Some(Game::from_deck(vorhand, deck))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substreams() {
+        let all: Vec<Deal> = GameGenerator::new(42).deals().take(12).collect();
+        let generator = GameGenerator::new(42);
+        for k in 0..3 {
+            let part: Vec<Deal> = generator.substream(k, 3).deals().take(4).collect();
+            for (i, deal) in part.into_iter().enumerate() {
+                assert_eq!(deal, all[k as usize + 3 * i]);
+            }
+        }
+        let games: Vec<Game> = GameGenerator::new(42).with_rotation().take(4).collect();
+        assert_eq!(games[1].vorhand(), PlayerId::ALL[1]);
+        assert_eq!(games[3].vorhand(), PlayerId::ALL[0]);
+        let deals: Vec<Deal> = GameGenerator::new(42)
+            .with_rotation()
+            .deals()
+            .take(4)
+            .collect();
+        for (game, deal) in games.iter().zip(&deals) {
+            assert_eq!(game.skat().cards(), deal.skat());
+            for player in PlayerId::ALL {
+                assert_eq!(game.player_id(player).cards(), deal.player(player));
+            }
+        }
+        assert_eq!(deals[2].skat(), all[2].skat());
+        assert_ne!(deals[1], all[1]);
+    }
+}
@@ -1,7 +1,24 @@
+pub mod bidding;
+pub mod canonical;
 pub mod card;
 pub mod cardholder;
+pub mod cardset;
+pub mod constraints;
+pub mod deal;
 pub mod deck;
+pub mod error;
+pub mod evaluate;
 pub mod game;
+pub mod generator;
+pub mod matadors;
+pub mod notation;
+pub mod seat;
+#[cfg(feature = "serde")]
+mod serde_impl;
+pub mod shuffle;
+pub mod table;
+pub mod unknown;
+pub mod value;
 
 pub fn add(left: u64, right: u64) -> u64 {
     left + right
@@ -0,0 +1,84 @@
+//! This module contains the [Matadors] (Spitzen) of a hand.
+
+use std::fmt::{self, Display};
+
+use crate::card::{Card, ALL_CARDS};
+use crate::cardset::CardSet;
+use crate::game::GameType;
+
+/// The matadors are the unbroken chain of the highest trumps from the Jack of Clubs down:
+/// the jacks, then in a suit game the trump suit Ace, 10, King, Queen, 9, 8, 7. \
+/// If the Jack of Clubs is held, the game is played 'with' the number of trumps in the chain,
+/// else 'without' the number of trumps missing above the highest trump held. \
+/// A suit game has up to 11 matadors, Grand up to 4. Null has no matadors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Matadors {
+    with: bool,
+    count: u8,
+}
+
+impl Matadors {
+    /// Calculates the matadors of the cards for the game. The cards should include the Skat.
+    pub fn new(cards: CardSet, game_type: &GameType) -> Matadors {
+        let mut trumps: Vec<Card> = ALL_CARDS
+            .iter()
+            .copied()
+            .filter(|card| card.is_trump(game_type))
+            .collect();
+        trumps.sort_by_key(|card| std::cmp::Reverse(card.trump_rank(game_type)));
+
+        let with = trumps.first().is_some_and(|&card| cards.contains(card));
+        let count = trumps
+            .iter()
+            .take_while(|&&card| cards.contains(card) == with)
+            .count() as u8;
+        Matadors { with, count }
+    }
+
+    /// True if played 'with' (the Jack of Clubs is held), false if 'without'.
+    pub fn with(&self) -> bool {
+        self.with
+    }
+
+    /// The number of matadors with or without.
+    pub fn count(&self) -> usize {
+        self.count as usize
+    }
+
+    /// The multiplier of the game value for the matadors: 'with 2 plays 3'.
+    pub fn multiplier(&self) -> u16 {
+        self.count as u16 + 1
+    }
+}
+
+/// "with 2" or "without 3"
+impl Display for Matadors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let with = if self.with { "with" } else { "without" };
+        write!(f, "{with} {}", self.count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand;
+
+    #[test]
+    fn chain() {
+        // with 4, plays 5 in Grand
+        let cards = hand!["KB", "PB", "HB", "CB", "KA", "KZ"];
+        let matadors = Matadors::new(cards, &GameType::Grand);
+        assert_eq!((matadors.with(), matadors.count()), (true, 4));
+        // the trump suit continues the chain in a suit game
+        let matadors = Matadors::new(cards, &GameType::Clubs);
+        assert_eq!((matadors.with(), matadors.multiplier()), (true, 7));
+
+        let matadors = Matadors::new(hand!["HB", "HA"], &GameType::Hearts);
+        assert_eq!((matadors.with(), matadors.count()), (false, 2));
+        let matadors = Matadors::new(hand!["H7"], &GameType::Hearts);
+        assert_eq!((matadors.with(), matadors.count()), (false, 10));
+        assert_eq!(matadors.to_string(), "without 10");
+    }
+}
@@ -0,0 +1,336 @@
+//! This module contains the [Notation] of cards and the parsing of cards and hands in all notations.
+//!
+//! Supported are
+//! - German letters: K = Kreuz, P = Pik, H = Herz, C = Caro with 7, 8, 9, B, D, K, Z, A, e.g. "KB"
+//! - English letters: C = Clubs, S = Spades, H = Hearts, D = Diamonds with 7, 8, 9, J, Q, K, T, A, e.g. "CJ"
+//! - Unicode suit symbols with the English ranks, e.g. "♣J"
+//! - German deck names: Eichel, Grün, Rot, Schellen with 7, 8, 9, 10, Unter, Ober, König, Daus, e.g. "Eichel-Unter"
+//! - German names: Kreuz, Pik, Herz, Karo with Sieben, Acht, Neun, Zehn, Bube, Dame, König, Ass, e.g. "Kreuz-Bube"
+//!
+//! For output there are also the English names, e.g. "Jack of Clubs", which can not be read back.
+//! All types which can be shown in a notation implement [NotationDisplay].
+//!
+//! The rank letters 10, U and O are accepted in all notations. \
+//! The letter C is Caro (Diamonds) in German and Clubs in English. When the notation is not given,
+//! it is detected from the other symbols of the hand, e.g. "K" or "P" as suit or "B", "D", "Z" as rank
+//! mean German. If this is not possible, the input is rejected as ambiguous.
+
+use std::fmt::{self, Display};
+
+use crate::card::{Card, Rank, Suit};
+use crate::error::ParseError;
+
+/// The way cards are written.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Notation {
+    /// "KB" for the Jack of Clubs (Kreuz Bube)
+    #[default]
+    German,
+    /// "CJ" for the Jack of Clubs
+    English,
+    /// "♣J" for the Jack of Clubs
+    Unicode,
+    /// "Eichel-Unter" for the Jack of Clubs
+    GermanDeck,
+    /// "Kreuz-Bube" for the Jack of Clubs
+    GermanNames,
+    /// "Jack of Clubs", for output only
+    EnglishNames,
+}
+
+/// Formatting in a chosen [Notation]. The `Display` implementation of these types uses [Notation::German].
+pub trait NotationDisplay {
+    fn fmt_notation(&self, f: &mut fmt::Formatter<'_>, notation: Notation) -> fmt::Result;
+
+    /// Returns a value which displays in the notation, e.g. `card.with_notation(Notation::English).to_string()`.
+    fn with_notation(&self, notation: Notation) -> WithNotation<'_, Self> {
+        WithNotation {
+            value: self,
+            notation,
+        }
+    }
+}
+
+/// Helper to display a value in a [Notation], see [NotationDisplay::with_notation].
+pub struct WithNotation<'a, T: ?Sized> {
+    value: &'a T,
+    notation: Notation,
+}
+
+impl<T: NotationDisplay + ?Sized> Display for WithNotation<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt_notation(f, self.notation)
+    }
+}
+
+/// German deck and German suit names, lower case.
+const SUIT_NAMES: [(&str, Suit); 9] = [
+    ("eichel", Suit::Clubs),
+    ("grün", Suit::Spades),
+    ("gruen", Suit::Spades),
+    ("rot", Suit::Hearts),
+    ("schellen", Suit::Diamonds),
+    ("kreuz", Suit::Clubs),
+    ("pik", Suit::Spades),
+    ("herz", Suit::Hearts),
+    ("karo", Suit::Diamonds),
+];
+
+/// The suit part of a card before the notation is known.
+#[derive(Debug, Clone, Copy)]
+enum SuitSymbol {
+    Suit(Suit, Option<Notation>),
+    /// 'C' is Caro in German and Clubs in English.
+    C,
+}
+
+struct Token {
+    suit: SuitSymbol,
+    rank: Rank,
+    rank_hint: Option<Notation>,
+}
+
+/// Parses a single card. The notation decides how the ambiguous letter C is read,
+/// if not given it is detected from the card.
+pub(crate) fn parse_card(card_named: &str, notation: Option<Notation>) -> Result<Card, ParseError> {
+    Ok(parse_cards(&[card_named], notation)?[0])
+}
+
+/// Parses the cards of a hand, the position in an error is the index of the token. \
+/// The notation decides how the ambiguous letter C is read, if not given it is detected from all cards.
+pub(crate) fn parse_cards(
+    card_names: &[&str],
+    notation: Option<Notation>,
+) -> Result<Vec<Card>, ParseError> {
+    let mut tokens = Vec::with_capacity(card_names.len());
+    for (position, card_named) in card_names.iter().enumerate() {
+        tokens.push(parse_token(card_named).map_err(|e| e.at(position))?);
+    }
+
+    let c_suit = match notation {
+        Some(Notation::English) => Some(Suit::Clubs),
+        Some(_) => Some(Suit::Diamonds),
+        None => detect_c_suit(&tokens),
+    };
+
+    let mut cards = Vec::with_capacity(tokens.len());
+    for (position, token) in tokens.iter().enumerate() {
+        let suit = match token.suit {
+            SuitSymbol::Suit(suit, _) => suit,
+            SuitSymbol::C => c_suit.ok_or(ParseError::AmbiguousSuit {
+                symbol: 'C',
+                position,
+            })?,
+        };
+        cards.push(Card::new(suit, token.rank));
+    }
+    Ok(cards)
+}
+
+/// Splits a hand like "KB PB HA HZ" or "♣J, ♠J, ♥A" into the card names.
+pub(crate) fn split_hand(hand: &str) -> Vec<&str> {
+    hand.split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Returns the suit of the letter C, detected from the other suit letters, else from the rank letters.
+fn detect_c_suit(tokens: &[Token]) -> Option<Suit> {
+    let suit_hints: Vec<Notation> = tokens
+        .iter()
+        .filter_map(|t| match t.suit {
+            SuitSymbol::Suit(_, hint) => hint,
+            SuitSymbol::C => None,
+        })
+        .collect();
+    if !suit_hints.is_empty() {
+        // mixed suit letters can not be resolved by the ranks
+        return c_suit_from_hints(&suit_hints);
+    }
+    let rank_hints: Vec<Notation> = tokens.iter().filter_map(|t| t.rank_hint).collect();
+    c_suit_from_hints(&rank_hints)
+}
+
+fn c_suit_from_hints(hints: &[Notation]) -> Option<Suit> {
+    if hints.is_empty() {
+        None
+    } else if hints.iter().all(|&hint| hint == Notation::German) {
+        Some(Suit::Diamonds)
+    } else if hints.iter().all(|&hint| hint == Notation::English) {
+        Some(Suit::Clubs)
+    } else {
+        None
+    }
+}
+
+fn parse_token(card_named: &str) -> Result<Token, ParseError> {
+    let length = card_named.chars().count();
+    let (suit, rank_part) = split_suit(card_named)?;
+    let rank_part = rank_part.strip_prefix('-').unwrap_or(rank_part);
+    let Some((rank, rank_hint)) = parse_rank(rank_part) else {
+        // a single unknown symbol or an unknown rank name, else the card has too many or too few symbols
+        let is_letter = matches!(suit, SuitSymbol::C | SuitSymbol::Suit(_, Some(_)));
+        let mut chars = rank_part.chars();
+        return match (chars.next(), chars.next()) {
+            (Some(symbol), None) => Err(ParseError::UnknownRank {
+                symbol,
+                position: 0,
+            }),
+            (Some(symbol), Some(_)) if !is_letter => Err(ParseError::UnknownRank {
+                symbol,
+                position: 0,
+            }),
+            _ => Err(ParseError::WrongLength {
+                length,
+                position: 0,
+            }),
+        };
+    };
+    Ok(Token {
+        suit,
+        rank,
+        rank_hint,
+    })
+}
+
+fn split_suit(card_named: &str) -> Result<(SuitSymbol, &str), ParseError> {
+    for (name, suit) in SUIT_NAMES {
+        if let Some(rest) = strip_name(card_named, name) {
+            return Ok((SuitSymbol::Suit(suit, None), rest));
+        }
+    }
+
+    let mut chars = card_named.chars();
+    let Some(symbol) = chars.next() else {
+        return Err(ParseError::WrongLength {
+            length: 0,
+            position: 0,
+        });
+    };
+    let suit = match symbol.to_ascii_uppercase() {
+        'K' => SuitSymbol::Suit(Suit::Clubs, Some(Notation::German)),
+        'P' => SuitSymbol::Suit(Suit::Spades, Some(Notation::German)),
+        'S' => SuitSymbol::Suit(Suit::Spades, Some(Notation::English)),
+        'D' => SuitSymbol::Suit(Suit::Diamonds, Some(Notation::English)),
+        'H' => SuitSymbol::Suit(Suit::Hearts, None),
+        'C' => SuitSymbol::C,
+        '♣' | '♧' => SuitSymbol::Suit(Suit::Clubs, None),
+        '♠' | '♤' => SuitSymbol::Suit(Suit::Spades, None),
+        '♥' | '♡' => SuitSymbol::Suit(Suit::Hearts, None),
+        '♦' | '♢' => SuitSymbol::Suit(Suit::Diamonds, None),
+        _ => {
+            return Err(ParseError::UnknownSuit {
+                symbol,
+                position: 0,
+            })
+        }
+    };
+    Ok((suit, chars.as_str()))
+}
+
+/// Strips the lower case name from the start ignoring case. Compared by chars, as the lower case
+/// of a char may differ in its length in bytes.
+fn strip_name<'a>(card_named: &'a str, name: &str) -> Option<&'a str> {
+    let mut chars = card_named.chars();
+    for expected in name.chars() {
+        let symbol = chars.next()?;
+        if !symbol.to_lowercase().eq([expected]) {
+            return None;
+        }
+    }
+    Some(chars.as_str())
+}
+
+/// Returns the rank and the notation it belongs to if it is a letter of only one notation.
+fn parse_rank(rank_named: &str) -> Option<(Rank, Option<Notation>)> {
+    let rank = match rank_named.to_lowercase().as_str() {
+        "7" | "sieben" => (Rank::Seven, None),
+        "8" | "acht" => (Rank::Eight, None),
+        "9" | "neun" => (Rank::Nine, None),
+        "10" | "zehn" => (Rank::Ten, None),
+        "z" => (Rank::Ten, Some(Notation::German)),
+        "t" => (Rank::Ten, Some(Notation::English)),
+        "b" => (Rank::Jack, Some(Notation::German)),
+        "j" => (Rank::Jack, Some(Notation::English)),
+        "u" | "unter" | "bube" => (Rank::Jack, None),
+        "d" => (Rank::Queen, Some(Notation::German)),
+        "q" => (Rank::Queen, Some(Notation::English)),
+        "o" | "ober" | "dame" => (Rank::Queen, None),
+        "k" | "könig" | "koenig" => (Rank::King, None),
+        "a" | "ass" | "daus" => (Rank::Ace, None),
+        _ => return None,
+    };
+    Some(rank)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cardset::CardSet;
+
+    #[test]
+    fn notations() {
+        let jack = Card::new(Suit::Clubs, Rank::Jack);
+        for name in ["KB", "KJ", "CJ", "♣J", "♣B", "Eichel-Unter", "eichelu"] {
+            assert_eq!(name.parse::<Card>(), Ok(jack), "{name}");
+        }
+        assert_eq!(
+            "Grün10".parse::<Card>(),
+            Ok(Card::new(Suit::Spades, Rank::Ten))
+        );
+        assert_eq!(
+            "CB".parse::<Card>(),
+            Ok(Card::new(Suit::Diamonds, Rank::Jack))
+        );
+    }
+
+    #[test]
+    fn ambiguous() {
+        assert_eq!(
+            "CA".parse::<Card>(),
+            Err(ParseError::AmbiguousSuit {
+                symbol: 'C',
+                position: 0
+            })
+        );
+        assert_eq!(
+            Card::parse_with("CA", Notation::English),
+            Ok(Card::new(Suit::Clubs, Rank::Ace))
+        );
+        // German suits in the hand, so C is Caro
+        let hand: CardSet = "KB PB CA".parse().unwrap();
+        assert!(hand.contains(Card::new(Suit::Diamonds, Rank::Ace)));
+        // English suits in the hand, so C is Clubs
+        let hand: CardSet = "SJ, DA, CA".parse().unwrap();
+        assert!(hand.contains(Card::new(Suit::Clubs, Rank::Ace)));
+        let hand = "KA SA CA".parse::<CardSet>();
+        assert_eq!(hand.unwrap_err().position(), Some(2));
+    }
+
+    #[test]
+    fn hand() {
+        let hand: CardSet = "♣J ♠J ♥A ♥10".parse().unwrap();
+        assert_eq!(hand, "KB PB HA HZ".parse().unwrap());
+        assert_eq!(
+            "KB PB KB".parse::<CardSet>(),
+            Err(ParseError::DuplicateCard {
+                card: Card::new(Suit::Clubs, Rank::Jack),
+                position: 2
+            })
+        );
+        // the Kelvin sign is a K with a different length in bytes than its lower case
+        assert_eq!(
+            "PI\u{212A}B".parse::<Card>(),
+            Ok(Card::new(Suit::Spades, Rank::Jack))
+        );
+        assert!("KA PI\u{212A}B".parse::<CardSet>().is_ok());
+        assert!("\u{212A}\u{212A}".parse::<Card>().is_err());
+        assert_eq!(
+            "KB ♥".parse::<CardSet>(),
+            Err(ParseError::WrongLength {
+                length: 1,
+                position: 1
+            })
+        );
+    }
+}
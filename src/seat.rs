@@ -0,0 +1,127 @@
+//! This module contains the [PlayerId] of the three players of a game and their [Seat] in it.
+
+use std::fmt::{self, Display};
+
+use crate::error::GameError;
+
+/// One of the three players of a game, player 1, 2 or 3. \
+/// The players sit clockwise in this order, so the player after player 3 is player 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "u8", into = "u8")
+)]
+pub struct PlayerId(u8);
+
+impl PlayerId {
+    pub const ALL: [PlayerId; 3] = [PlayerId(0), PlayerId(1), PlayerId(2)];
+
+    /// The player with the index 0..3.
+    /// # Returns
+    /// None if the index is 3 or larger.
+    pub const fn new(index: usize) -> Option<PlayerId> {
+        if index < 3 {
+            Some(PlayerId(index as u8))
+        } else {
+            None
+        }
+    }
+
+    /// The index 0..3 of the player.
+    pub const fn index(&self) -> usize {
+        self.0 as usize
+    }
+
+    /// The player to the left, who plays after this player.
+    pub const fn next(&self) -> PlayerId {
+        PlayerId((self.0 + 1) % 3)
+    }
+}
+
+impl TryFrom<usize> for PlayerId {
+    type Error = GameError;
+
+    fn try_from(index: usize) -> Result<Self, Self::Error> {
+        PlayerId::new(index).ok_or(GameError::InvalidPlayer { index })
+    }
+}
+
+impl TryFrom<u8> for PlayerId {
+    type Error = GameError;
+
+    fn try_from(index: u8) -> Result<Self, Self::Error> {
+        PlayerId::try_from(usize::from(index))
+    }
+}
+
+impl From<PlayerId> for u8 {
+    fn from(player: PlayerId) -> Self {
+        player.0
+    }
+}
+
+/// "Player 1" for index 0
+impl Display for PlayerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Player {}", self.0 + 1)
+    }
+}
+
+/// The seat of a player in a game. Vorhand sits left of the dealer, gets the first cards and
+/// plays the first trick, Mittelhand and Hinterhand follow clockwise. \
+/// With three players at the table the dealer is Hinterhand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Seat {
+    Vorhand,
+    Mittelhand,
+    Hinterhand,
+}
+
+impl Seat {
+    pub const ALL: [Seat; 3] = [Seat::Vorhand, Seat::Mittelhand, Seat::Hinterhand];
+
+    /// The player in this seat when the given player is Vorhand.
+    pub const fn player(&self, vorhand: PlayerId) -> PlayerId {
+        PlayerId((vorhand.0 + *self as u8) % 3)
+    }
+
+    /// The seat of the player when the given player is Vorhand.
+    pub const fn of(player: PlayerId, vorhand: PlayerId) -> Seat {
+        Seat::ALL[((player.0 + 3 - vorhand.0) % 3) as usize]
+    }
+}
+
+impl Display for Seat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Seat::Vorhand => "Vorhand",
+            Seat::Mittelhand => "Mittelhand",
+            Seat::Hinterhand => "Hinterhand",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seats() {
+        let vorhand = PlayerId::new(2).unwrap();
+        assert_eq!(Seat::Vorhand.player(vorhand), vorhand);
+        assert_eq!(Seat::Mittelhand.player(vorhand), PlayerId::ALL[0]);
+        assert_eq!(Seat::Hinterhand.player(vorhand), PlayerId::ALL[1]);
+        for player in PlayerId::ALL {
+            assert_eq!(Seat::of(player, vorhand).player(vorhand), player);
+        }
+        assert_eq!(PlayerId::new(3), None);
+        assert_eq!(
+            PlayerId::try_from(3usize),
+            Err(GameError::InvalidPlayer { index: 3 })
+        );
+        assert_eq!(vorhand.next(), PlayerId::ALL[0]);
+    }
+}
@@ -0,0 +1,239 @@
+//! Serialize and Deserialize for the types which need a compact form or a check of their cards,
+//! active with the feature `serde`.
+//!
+//! In human readable formats like JSON a [Card] is written in German notation, e.g. "KB",
+//! and a [CardSet] as list like "KB, PB, HA". In binary formats the card is its [Card::index]
+//! and the set its bit mask. \
+//! A [Deck] is written as its cards from the bottom to the top, a [Deal] as the cards of the
+//! players and the Skat. Both are checked to hold each card once when read. \
+//! A [Table] is checked to seat 3 or 4 people with a score each when read.
+
+use std::fmt;
+
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::card::Card;
+use crate::cardset::CardSet;
+use crate::deal::Deal;
+use crate::deck::Deck;
+use crate::notation::Notation;
+use crate::table::Table;
+
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_u8(self.index() as u8)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct CardVisitor;
+
+        impl Visitor<'_> for CardVisitor {
+            type Value = Card;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a card like \"KB\" or a card index 0..32")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Card, E> {
+                Card::parse_with(v, Notation::German).map_err(E::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Card, E> {
+                Card::from_index(v as usize)
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(CardVisitor)
+        } else {
+            deserializer.deserialize_u8(CardVisitor)
+        }
+    }
+}
+
+impl Serialize for CardSet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_u32(self.bits())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CardSet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct CardSetVisitor;
+
+        impl Visitor<'_> for CardSetVisitor {
+            type Value = CardSet;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "cards like \"KB, PB, HA\" or a 32 bit mask")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<CardSet, E> {
+                CardSet::parse_with(v, Notation::German).map_err(E::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<CardSet, E> {
+                u32::try_from(v)
+                    .map(CardSet::from_bits)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(CardSetVisitor)
+        } else {
+            deserializer.deserialize_u32(CardSetVisitor)
+        }
+    }
+}
+
+impl Serialize for Deck {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.cards().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Deck {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let cards = Vec::<Card>::deserialize(deserializer)?;
+        Deck::from_cards(cards).map_err(de::Error::custom)
+    }
+}
+
+/// The fields of a [Deal], which is only created after checking the cards.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Deal")]
+struct DealFields {
+    players: [CardSet; 3],
+    skat: CardSet,
+}
+
+impl Serialize for Deal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        DealFields {
+            players: *self.players(),
+            skat: self.skat(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Deal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = DealFields::deserialize(deserializer)?;
+        Deal::new(fields.players, fields.skat).map_err(de::Error::custom)
+    }
+}
+
+/// The fields of a [Table], which is only created after checking them.
+#[derive(Deserialize)]
+#[serde(rename = "Table")]
+struct TableFields {
+    names: Vec<String>,
+    dealer: usize,
+    games: usize,
+    seating: Option<[usize; 3]>,
+    scores: Vec<i32>,
+}
+
+impl<'de> Deserialize<'de> for Table {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = TableFields::deserialize(deserializer)?;
+        Table::from_fields(
+            fields.names,
+            fields.dealer,
+            fields.games,
+            fields.seating,
+            fields.scores,
+        )
+        .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bidding::{BidAction, BidValue, Bidding};
+    use crate::card::{Rank, Suit};
+    use crate::cardholder::CardHolder;
+    use crate::game::Game;
+    use crate::seat::{PlayerId, Seat};
+
+    use super::*;
+
+    #[test]
+    fn json() {
+        let card = Card::new(Suit::Diamonds, Rank::Ace);
+        assert_eq!(serde_json::to_string(&card).unwrap(), "\"CA\"");
+        assert_eq!(serde_json::from_str::<Card>("\"CA\"").unwrap(), card);
+
+        let cards: CardSet = "KB PB HA".parse().unwrap();
+        let json = serde_json::to_string(&cards).unwrap();
+        assert_eq!(serde_json::from_str::<CardSet>(&json).unwrap(), cards);
+
+        let holder = CardHolder::new("Player 1", cards);
+        let json = serde_json::to_string(&holder).unwrap();
+        let holder_back: CardHolder = serde_json::from_str(&json).unwrap();
+        assert_eq!(holder_back.cards(), cards);
+
+        let game = Game::new(PlayerId::ALL[0]);
+        let json = serde_json::to_string(&game).unwrap();
+        let game_back: Game = serde_json::from_str(&json).unwrap();
+        assert_eq!(game_back.skat().cards(), game.skat().cards());
+        assert!(game_back.validate().is_ok());
+        let vorhand_7 = json.replace("\"vorhand\":0", "\"vorhand\":7");
+        assert_ne!(vorhand_7, json);
+        assert!(serde_json::from_str::<Game>(&vorhand_7).is_err());
+    }
+
+    #[test]
+    fn checked_json() {
+        let deck = Deck::with_seed(3);
+        let json = serde_json::to_string(&deck).unwrap();
+        let deck_back: Deck = serde_json::from_str(&json).unwrap();
+        assert_eq!(deck_back.cards(), deck.cards());
+        assert!(serde_json::from_str::<Deck>("[\"KB\", \"KB\"]").is_err());
+
+        let deal = deck.deal_cards();
+        let json = serde_json::to_string(&deal).unwrap();
+        assert_eq!(serde_json::from_str::<Deal>(&json).unwrap(), deal);
+        let twice = json.replacen(&deal.skat().iter().next().unwrap().to_string(), "KB", 1);
+        assert!(serde_json::from_str::<Deal>(&twice).is_err());
+
+        let mut table = Table::with_four(["Anna", "Ben", "Cem", "Dora"]);
+        table.next_game_with_seed(1);
+        let json = serde_json::to_string(&table).unwrap();
+        assert_eq!(serde_json::from_str::<Table>(&json).unwrap(), table);
+        let empty = r#"{"names":[],"dealer":0,"games":0,"seating":null,"scores":[]}"#;
+        assert!(serde_json::from_str::<Table>(empty).is_err());
+        let no_scores = json.replace("\"scores\":[0,0,0,0]", "\"scores\":[0]");
+        assert_ne!(no_scores, json);
+        assert!(serde_json::from_str::<Table>(&no_scores).is_err());
+        let dealer_away = json.replace("\"dealer\":0", "\"dealer\":4");
+        assert_ne!(dealer_away, json);
+        assert!(serde_json::from_str::<Table>(&dealer_away).is_err());
+
+        assert_eq!(serde_json::to_string(&PlayerId::ALL[2]).unwrap(), "2");
+        assert!(serde_json::from_str::<PlayerId>("3").is_err());
+
+        let vorhand = PlayerId::ALL[0];
+        let mut bidding = Bidding::new(vorhand);
+        let mittelhand = Seat::Mittelhand.player(vorhand);
+        bidding
+            .act(mittelhand, BidAction::Bid(BidValue::MIN))
+            .unwrap();
+        let json = serde_json::to_string(&bidding).unwrap();
+        assert_eq!(serde_json::from_str::<Bidding>(&json).unwrap(), bidding);
+    }
+}
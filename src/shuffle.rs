@@ -0,0 +1,232 @@
+//! This module contains the reproducible shuffle of the cards. \
+//! A seed gives the same deal across versions of this crate and of the rand crate, as long as the
+//! same [ShuffleVersion] is used. The random number generator and the shuffle are implemented here
+//! for that reason and must never change. A changed algorithm gets a new version.
+//!
+//! [ShuffleVersion::V1]:
+//! - Random numbers: SplitMix64 with the seed as initial state.
+//! - A random number below n is the upper 64 bits of the 128 bit product of a random number and n,
+//!   rejecting random numbers where the lower 64 bits are below `2^64 mod n` (Lemire's method).
+//! - Shuffle: Fisher-Yates from the last card down, card i is swapped with a random card 0..=i.
+//!
+//! Besides the uniform shuffle, [ShuffleModel] imitates how cards are shuffled at a real table,
+//! to study how far such a deck is from a uniform one.
+
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+
+use crate::card::Card;
+
+/// The version of the seeded shuffle algorithm, see the [module description](self).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShuffleVersion {
+    #[default]
+    V1,
+}
+
+/// The increment of the SplitMix64 state.
+const GOLDEN_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// The stable random number generator of the seeded shuffle (SplitMix64). \
+/// It implements [RngCore], so it can be used wherever rand expects a generator.
+#[derive(Debug, Clone)]
+pub struct SkatRng {
+    state: u64,
+}
+
+impl SkatRng {
+    pub fn new(seed: u64) -> SkatRng {
+        SkatRng { state: seed }
+    }
+
+    /// Returns the n-th number (counting from 0) generated from the seed, without generating
+    /// the numbers before it.
+    pub fn nth_from(seed: u64, n: u64) -> u64 {
+        SkatRng::new(seed.wrapping_add(n.wrapping_mul(GOLDEN_GAMMA))).next_u64()
+    }
+
+    /// Returns a random number in 0..n without bias.
+    /// # Panics
+    /// If n is 0.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "no number below 0");
+        let threshold = n.wrapping_neg() % n;
+        loop {
+            let product = self.next_u64() as u128 * n as u128;
+            if product as u64 >= threshold {
+                return (product >> 64) as u64;
+            }
+        }
+    }
+}
+
+impl RngCore for SkatRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        for chunk in dst.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
+
+/// Shuffles the cards with the seed in the given version of the algorithm.
+pub(crate) fn shuffle_seeded(cards: &mut [Card], seed: u64, version: ShuffleVersion) {
+    match version {
+        ShuffleVersion::V1 => {
+            let mut rng = SkatRng::new(seed);
+            for i in (1..cards.len()).rev() {
+                let j = rng.below(i as u64 + 1) as usize;
+                cards.swap(i, j);
+            }
+        }
+    }
+}
+
+/// Minimal number of cards in each packet when cutting the deck (Abheben).
+pub const MIN_CUT: usize = 4;
+
+/// The largest packet taken in one move of the overhand shuffle.
+const MAX_OVERHAND_PACKET: usize = 8;
+
+/// A way of shuffling the deck, see [crate::deck::Deck::apply]. The top of the deck is the end of the cards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShuffleModel {
+    /// Every order is equally likely.
+    Uniform,
+    /// Gilbert-Shannon-Reeds riffle: the deck is cut binomially and the packets are interleaved,
+    /// each card falling from a packet with a probability proportional to the packet's size.
+    Riffle { passes: usize },
+    /// Packets of 1 to 8 cards are taken from the top and put on a new pile, in each pass
+    /// until the deck is used up.
+    Overhand { passes: usize },
+    /// The given number of cards is taken from the top and put below the rest.
+    Cut { position: usize },
+    /// Cut at a random position, leaving at least [MIN_CUT] cards in each packet (Abheben).
+    RandomCut,
+}
+
+impl ShuffleModel {
+    /// Shuffles the cards, the last card is the top of the deck.
+    pub fn apply<R: Rng + ?Sized>(&self, cards: &mut Vec<Card>, rng: &mut R) {
+        match *self {
+            ShuffleModel::Uniform => cards.shuffle(rng),
+            ShuffleModel::Riffle { passes } => {
+                for _ in 0..passes {
+                    riffle(cards, rng);
+                }
+            }
+            ShuffleModel::Overhand { passes } => {
+                for _ in 0..passes {
+                    overhand(cards, rng);
+                }
+            }
+            ShuffleModel::Cut { position } => {
+                let position = position.min(cards.len());
+                cards.rotate_right(position);
+            }
+            ShuffleModel::RandomCut => {
+                if cards.len() >= 2 * MIN_CUT {
+                    let position = rng.random_range(MIN_CUT..=cards.len() - MIN_CUT);
+                    cards.rotate_right(position);
+                }
+            }
+        }
+    }
+}
+
+/// One riffle of the Gilbert-Shannon-Reeds model.
+fn riffle<R: Rng + ?Sized>(cards: &mut Vec<Card>, rng: &mut R) {
+    let top = (0..cards.len()).filter(|_| rng.random_bool(0.5)).count();
+    let (lower, upper) = cards.split_at(cards.len() - top);
+    // the new pile is built from the bottom, the cards fall from the bottom of the packets
+    let (mut l, mut u) = (0, 0);
+    let mut pile = Vec::with_capacity(cards.len());
+    while l < lower.len() || u < upper.len() {
+        let left_in_lower = (lower.len() - l) as u64;
+        let left_in_upper = (upper.len() - u) as u64;
+        if rng.random_range(0..left_in_lower + left_in_upper) < left_in_lower {
+            pile.push(lower[l]);
+            l += 1;
+        } else {
+            pile.push(upper[u]);
+            u += 1;
+        }
+    }
+    *cards = pile;
+}
+
+/// One pass of the overhand shuffle.
+fn overhand<R: Rng + ?Sized>(cards: &mut Vec<Card>, rng: &mut R) {
+    // packets taken from the top end up in reverse packet order, the cards in a packet keep their order:
+    // the first packet is the bottom of the new pile, each later packet is put on top
+    let mut pile = Vec::with_capacity(cards.len());
+    while !cards.is_empty() {
+        let size = rng.random_range(1..=MAX_OVERHAND_PACKET).min(cards.len());
+        let packet = cards.split_off(cards.len() - size);
+        pile.extend(packet);
+    }
+    *cards = pile;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::ALL_CARDS;
+
+    #[test]
+    fn models() {
+        let mut rng = SkatRng::new(3);
+        let models = [
+            ShuffleModel::Uniform,
+            ShuffleModel::Riffle { passes: 3 },
+            ShuffleModel::Overhand { passes: 2 },
+            ShuffleModel::RandomCut,
+        ];
+        for model in models {
+            let mut cards = ALL_CARDS.to_vec();
+            model.apply(&mut cards, &mut rng);
+            let mut sorted = cards.clone();
+            sorted.sort_by_key(|card| card.index());
+            assert_eq!(sorted, ALL_CARDS.to_vec(), "{model:?}");
+        }
+
+        let mut rng = SkatRng::new(9);
+        rng.next_u64();
+        assert_eq!(rng.next_u64(), SkatRng::nth_from(9, 1));
+
+        // the old top packet is at the bottom, the old bottom packet on top,
+        // the cards within a packet keep their order
+        let mut cards = ALL_CARDS.to_vec();
+        ShuffleModel::Overhand { passes: 1 }.apply(&mut cards, &mut rng);
+        let position = |card: Card| cards.iter().position(|&c| c == card).unwrap();
+        assert!(position(ALL_CARDS[31]) < MAX_OVERHAND_PACKET);
+        assert!(position(ALL_CARDS[0]) >= ALL_CARDS.len() - MAX_OVERHAND_PACKET);
+        let packets = 1 + cards
+            .windows(2)
+            .filter(|w| w[0].index() > w[1].index())
+            .count();
+        assert!(packets >= ALL_CARDS.len().div_ceil(MAX_OVERHAND_PACKET));
+        assert_ne!(cards, ALL_CARDS.to_vec());
+
+        let mut cards = ALL_CARDS.to_vec();
+        ShuffleModel::Overhand { passes: 5 }.apply(&mut cards, &mut rng);
+        assert_ne!(cards, ALL_CARDS.to_vec());
+
+        let mut cards = ALL_CARDS.to_vec();
+        ShuffleModel::Cut { position: 4 }.apply(&mut cards, &mut rng);
+        assert_eq!(cards[0], ALL_CARDS[28]);
+        assert_eq!(cards[31], ALL_CARDS[27]);
+    }
+}
@@ -0,0 +1,200 @@
+//! This module contains the [Table], the people playing a series of games. \
+//! The dealer moves clockwise after each game, the player left of the dealer is Vorhand.
+//! At a table of four the dealer (Geber) sits out and the other three play.
+
+use crate::deck::Deck;
+use crate::game::Game;
+use crate::seat::PlayerId;
+
+/// The people at a table playing a series of games. They are numbered by their position at the
+/// table in clockwise order. \
+/// The active players of a game get the [PlayerId]s in the order of their positions, Vorhand is
+/// the one left of the dealer. With three people the position is their [PlayerId] in each game,
+/// with four people use [Table::person] to find the person of a player.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Table {
+    names: Vec<String>,
+    /// Position of the dealer of the next game.
+    dealer: usize,
+    games: usize,
+    /// Positions of the players 1..3 of the last game dealt.
+    seating: Option<[usize; 3]>,
+    scores: Vec<i32>,
+}
+
+impl Table {
+    /// Creates the table for three, the last person deals the first game so the first one is Vorhand.
+    pub fn new(names: [&str; 3]) -> Table {
+        Table::with_names(&names)
+    }
+
+    /// Creates the table for four, the last person deals the first game and sits out.
+    pub fn with_four(names: [&str; 4]) -> Table {
+        Table::with_names(&names)
+    }
+
+    fn with_names(names: &[&str]) -> Table {
+        Table {
+            names: names.iter().map(|name| name.to_string()).collect(),
+            dealer: names.len() - 1,
+            games: 0,
+            seating: None,
+            scores: vec![0; names.len()],
+        }
+    }
+
+    /// Creates the table from its fields, e.g. when deserializing.
+    /// # Errors
+    /// The reason if there are not 3 or 4 names, not a score for each name,
+    /// or the dealer or the seating are not at the table.
+    #[cfg(feature = "serde")]
+    pub(crate) fn from_fields(
+        names: Vec<String>,
+        dealer: usize,
+        games: usize,
+        seating: Option<[usize; 3]>,
+        scores: Vec<i32>,
+    ) -> Result<Table, &'static str> {
+        let count = names.len();
+        if !(3..=4).contains(&count) {
+            return Err("a table needs 3 or 4 names");
+        }
+        if scores.len() != count {
+            return Err("a table needs a score for each name");
+        }
+        if dealer >= count {
+            return Err("the dealer is not at the table");
+        }
+        if let Some([first, second, third]) = seating {
+            if first >= count || second >= count || third >= count {
+                return Err("a player of the last game is not at the table");
+            }
+            if first == second || first == third || second == third {
+                return Err("a person plays twice in the last game");
+            }
+        }
+        Ok(Table {
+            names,
+            dealer,
+            games,
+            seating,
+            scores,
+        })
+    }
+
+    /// The names in the order of the positions at the table.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Position of the dealer of the next game.
+    pub fn dealer(&self) -> usize {
+        self.dealer
+    }
+
+    /// Number of games dealt at this table.
+    pub fn games(&self) -> usize {
+        self.games
+    }
+
+    /// The position of the person playing as the player in the last game dealt,
+    /// None before the first game.
+    pub fn person(&self, player: PlayerId) -> Option<usize> {
+        self.seating.map(|seating| seating[player.index()])
+    }
+
+    /// The position of the dealer who sat out the last game dealt, None at a table of three.
+    /// The dealer may look at the cards but does not play.
+    pub fn sitting_out(&self) -> Option<usize> {
+        let seating = self.seating?;
+        (0..self.names.len()).find(|position| !seating.contains(position))
+    }
+
+    /// Credits the points to the person playing as the player in the last game dealt.
+    /// # Returns
+    /// The position of the person, None if no game was dealt yet.
+    pub fn add_score(&mut self, player: PlayerId, points: i32) -> Option<usize> {
+        let person = self.person(player)?;
+        self.scores[person] += points;
+        Some(person)
+    }
+
+    /// The total points of each person in the order of the positions.
+    pub fn scores(&self) -> &[i32] {
+        &self.scores
+    }
+
+    /// Deals the next game with a shuffled deck and moves the dealer to the next position.
+    pub fn next_game(&mut self) -> Game {
+        self.next_game_from_deck(Deck::new(true))
+    }
+
+    /// Deals the next game with the deck shuffled from the seed, see [Deck::with_seed].
+    pub fn next_game_with_seed(&mut self, seed: u64) -> Game {
+        self.next_game_from_deck(Deck::with_seed(seed))
+    }
+
+    /// Deals the next game from the deck to the active players, see [Game::from_deck].
+    pub fn next_game_from_deck(&mut self, deck: Deck) -> Game {
+        let count = self.names.len();
+        // at a table of four the dealer sits out
+        let mut active = (0..count).filter(|&position| count == 3 || position != self.dealer);
+        let seating = [(); 3].map(|_| active.next().unwrap_or_default());
+        let left_of_dealer = (self.dealer + 1) % count;
+        let vorhand = PlayerId::ALL[seating
+            .iter()
+            .position(|&position| position == left_of_dealer)
+            .unwrap_or_default()];
+        let mut game = Game::from_deck(vorhand, deck);
+        game.set_names(seating.map(|position| self.names[position].as_str()));
+        self.seating = Some(seating);
+        self.dealer = (self.dealer + 1) % count;
+        self.games += 1;
+        game
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seat::Seat;
+
+    #[test]
+    fn rotation() {
+        let mut table = Table::new(["Anna", "Ben", "Cem"]);
+        let game = table.next_game_with_seed(1);
+        assert_eq!(game.player_at(Seat::Vorhand).name(), "Anna");
+        assert_eq!(game.vorhand(), PlayerId::ALL[0]);
+        assert_eq!(game.player_at(Seat::Hinterhand).name(), "Cem");
+        let game = table.next_game_with_seed(2);
+        assert_eq!(game.player_at(Seat::Vorhand).name(), "Ben");
+        assert_eq!(game.player_at(Seat::Hinterhand).name(), "Anna");
+        assert_eq!((table.games(), table.dealer()), (2, 1));
+        assert_eq!(table.sitting_out(), None);
+    }
+
+    #[test]
+    fn four_players() {
+        let mut table = Table::with_four(["Anna", "Ben", "Cem", "Dora"]);
+        let game = table.next_game_with_seed(1);
+        assert_eq!(table.sitting_out(), Some(3));
+        assert_eq!(game.player_at(Seat::Vorhand).name(), "Anna");
+        assert_eq!(game.player_at(Seat::Hinterhand).name(), "Cem");
+
+        let game = table.next_game_with_seed(2);
+        assert_eq!(table.sitting_out(), Some(0));
+        assert_eq!(game.player_at(Seat::Vorhand).name(), "Ben");
+        assert_eq!(game.player_at(Seat::Hinterhand).name(), "Dora");
+        let hinterhand = Seat::Hinterhand.player(game.vorhand());
+        assert_eq!(table.add_score(hinterhand, 48), Some(3));
+        assert_eq!(table.scores(), &[0, 0, 0, 48]);
+
+        // the players are numbered by their positions, Vorhand sits left of the dealer
+        let game = table.next_game_with_seed(3);
+        assert_eq!(table.sitting_out(), Some(1));
+        assert_eq!(game.vorhand(), PlayerId::ALL[1]);
+        assert_eq!(table.person(game.vorhand()), Some(2));
+        assert_eq!(game.player_at(Seat::Hinterhand).name(), "Anna");
+    }
+}
@@ -0,0 +1,169 @@
+//! This module enumerates the cards unknown to a player: all possible Skats and all distributions
+//! of the remaining cards to the two opponents. As all deals are equally likely, exact probabilities
+//! can be calculated by counting instead of sampling.
+
+use crate::cardholder::{CardHolder, HAND_SIZE, SKAT_SIZE};
+use crate::cardset::CardSet;
+use crate::deal::{binomial, Holder};
+use crate::error::DealError;
+use crate::seat::PlayerId;
+
+/// One possible distribution of the unknown cards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Distribution {
+    /// The cards of the next and the second next player.
+    pub opponents: [CardSet; 2],
+    pub skat: CardSet,
+}
+
+/// The cards a player does not see, from the view of one player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownCards {
+    unknown: CardSet,
+    /// The Skat if it is known, e.g. by the declarer after picking it up.
+    skat: Option<CardSet>,
+}
+
+impl UnknownCards {
+    /// The unknown cards for the player holding these 10 cards.
+    pub fn new(player: PlayerId, hand: &CardHolder) -> Result<UnknownCards, DealError> {
+        let hand = hand.cards();
+        if hand.len() != HAND_SIZE {
+            return Err(DealError::WrongCardCount {
+                holder: Holder::Player(player),
+                expected: HAND_SIZE,
+                found: hand.len(),
+            });
+        }
+        Ok(UnknownCards {
+            unknown: !hand,
+            skat: None,
+        })
+    }
+
+    /// The unknown cards for a player who also knows the Skat. Only the opponents' cards are unknown.
+    pub fn with_skat(
+        player: PlayerId,
+        hand: &CardHolder,
+        skat: &CardHolder,
+    ) -> Result<UnknownCards, DealError> {
+        let mut unknown = UnknownCards::new(player, hand)?;
+        let skat = skat.cards();
+        if skat.len() != SKAT_SIZE {
+            return Err(DealError::WrongCardCount {
+                holder: Holder::Skat,
+                expected: SKAT_SIZE,
+                found: skat.len(),
+            });
+        }
+        if let Some(card) = (skat - unknown.unknown).iter().next() {
+            return Err(DealError::DuplicateCard { card });
+        }
+        unknown.unknown -= skat;
+        unknown.skat = Some(skat);
+        Ok(unknown)
+    }
+
+    /// The cards held by the opponents or lying in the unknown Skat.
+    pub fn cards(&self) -> CardSet {
+        self.unknown
+    }
+
+    /// All possible Skats, each equally likely.
+    pub fn skats(&self) -> Box<dyn Iterator<Item = CardSet>> {
+        match self.skat {
+            Some(skat) => Box::new(std::iter::once(skat)),
+            None => Box::new(self.unknown.subsets(SKAT_SIZE)),
+        }
+    }
+
+    /// Number of possible Skats, 231 if the Skat is unknown.
+    pub fn skat_count(&self) -> u128 {
+        match self.skat {
+            Some(_) => 1,
+            None => binomial(self.unknown.len(), SKAT_SIZE),
+        }
+    }
+
+    /// All distributions of the unknown cards to the opponents and the Skat, each equally likely.
+    pub fn distributions(&self) -> impl Iterator<Item = Distribution> {
+        let unknown = self.unknown;
+        self.skats().flat_map(move |skat| {
+            let rest = unknown - skat;
+            rest.subsets(HAND_SIZE).map(move |next| Distribution {
+                opponents: [next, rest - next],
+                skat,
+            })
+        })
+    }
+
+    /// Number of distributions of the opponents' cards for each Skat: 184,756.
+    pub fn distributions_per_skat(&self) -> u128 {
+        binomial(2 * HAND_SIZE, HAND_SIZE)
+    }
+
+    /// Number of all distributions, see [UnknownCards::distributions].
+    pub fn distribution_count(&self) -> u128 {
+        self.skat_count() * self.distributions_per_skat()
+    }
+
+    /// The exact probability that the Skat fulfills the condition,
+    /// e.g. `|skat| skat.num_jacks() > 0` for finding a jack in the Skat.
+    pub fn skat_probability<F: Fn(CardSet) -> bool>(&self, condition: F) -> f64 {
+        let hits = self.skats().filter(|&skat| condition(skat)).count();
+        hits as f64 / self.skat_count() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand;
+
+    #[test]
+    fn counts() {
+        let player = CardHolder::new(
+            "Player",
+            hand!["KB", "PB", "HB", "KA", "KZ", "KK", "KD", "K9", "K8", "K7"],
+        );
+        let unknown = UnknownCards::new(PlayerId::ALL[0], &player).unwrap();
+        assert_eq!(unknown.skat_count(), 231);
+        assert_eq!(unknown.skats().count(), 231);
+        assert_eq!(unknown.distribution_count(), 231 * 184_756);
+        // the 4th jack lies in the Skat in 21 of 231 cases
+        let p = unknown.skat_probability(|skat| skat.num_jacks() == 1);
+        assert!((p - 21.0 / 231.0).abs() < 1e-12);
+
+        let skat = CardHolder::new("Skat", hand!["CB", "C7"]);
+        let unknown = UnknownCards::with_skat(PlayerId::ALL[0], &player, &skat).unwrap();
+        assert_eq!(unknown.distributions().count(), 184_756);
+        assert!(unknown
+            .distributions()
+            .take(100)
+            .all(|d| d.opponents[0].len() == 10 && (d.opponents[0] & d.opponents[1]).is_empty()));
+    }
+
+    #[test]
+    fn errors() {
+        let player = CardHolder::new(
+            "Player",
+            hand!["KB", "PB", "HB", "KA", "KZ", "KK", "KD", "K9", "K8", "K7"],
+        );
+        let overlapping = CardHolder::new("Skat", hand!["KB", "C7"]);
+        assert_eq!(
+            UnknownCards::with_skat(PlayerId::ALL[0], &player, &overlapping),
+            Err(DealError::DuplicateCard {
+                card: crate::card!("KB")
+            })
+        );
+        let nine = CardHolder::new("Player", player.cards() - hand!["K7"]);
+        assert_eq!(
+            UnknownCards::new(PlayerId::ALL[1], &nine),
+            Err(DealError::WrongCardCount {
+                holder: Holder::Player(PlayerId::ALL[1]),
+                expected: HAND_SIZE,
+                found: 9
+            })
+        );
+    }
+}
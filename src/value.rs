@@ -0,0 +1,440 @@
+//! This module contains the game value (Spielwert), see [GameValue].
+//!
+//! A suit game or Grand is worth its base value times the multiplier: the matadors plus one for
+//! each level reached. Null has a fixed value.
+
+use std::fmt::{self, Display};
+
+use crate::bidding::BidValue;
+use crate::card::ALL_SUITS;
+use crate::game::GameType;
+use crate::matadors::Matadors;
+
+/// Base values of the suit games Clubs, Spades, Hearts, Diamonds,
+/// see [crate::card::Suit::suit_reiz_factor].
+pub(crate) const SUIT_BASE_VALUES: [u16; 4] = {
+    let mut values = [0; 4];
+    let mut i = 0;
+    while i < ALL_SUITS.len() {
+        values[i] = ALL_SUITS[i].suit_reiz_factor() as u16;
+        i += 1;
+    }
+    values
+};
+
+/// Base value of Grand.
+pub(crate) const GRAND_BASE_VALUE: u16 = 24;
+
+/// Values of Null, Null Hand, Null Ouvert, Null Ouvert Hand.
+pub const NULL_VALUES: [u16; 4] = [23, 35, 46, 59];
+
+/// The declarer reaches Schneider with this many card points, the opponents with 90 as well.
+const SCHNEIDER_POINTS: u8 = 90;
+
+/// The declarer wins a suit game or Grand with more than half of the 120 card points.
+const WINNING_POINTS: u8 = 61;
+
+const ALL_TRICKS: u8 = 10;
+
+/// The game announced by the declarer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Contract {
+    game_type: GameType,
+    hand: bool,
+    schneider_announced: bool,
+    schwarz_announced: bool,
+    ouvert: bool,
+}
+
+impl Contract {
+    pub fn new(game_type: GameType) -> Contract {
+        Contract {
+            game_type,
+            hand: false,
+            schneider_announced: false,
+            schwarz_announced: false,
+            ouvert: false,
+        }
+    }
+
+    /// Played without taking up the Skat.
+    pub fn hand(mut self) -> Contract {
+        self.hand = true;
+        self
+    }
+
+    /// Schneider can only be announced in a hand game.
+    pub fn schneider_announced(mut self) -> Contract {
+        self.schneider_announced = true;
+        self.hand()
+    }
+
+    /// Schwarz announced includes Schneider announced.
+    pub fn schwarz_announced(mut self) -> Contract {
+        self.schwarz_announced = true;
+        self.schneider_announced()
+    }
+
+    /// Ouvert in a suit game or Grand includes Schwarz announced, Null Ouvert may take the Skat.
+    pub fn ouvert(mut self) -> Contract {
+        self.ouvert = true;
+        if self.game_type == GameType::Null {
+            self
+        } else {
+            self.schwarz_announced()
+        }
+    }
+
+    pub fn game_type(&self) -> GameType {
+        self.game_type
+    }
+
+    pub fn is_hand(&self) -> bool {
+        self.hand
+    }
+
+    pub fn is_schneider_announced(&self) -> bool {
+        self.schneider_announced
+    }
+
+    pub fn is_schwarz_announced(&self) -> bool {
+        self.schwarz_announced
+    }
+
+    pub fn is_ouvert(&self) -> bool {
+        self.ouvert
+    }
+
+    /// The base value: 9, 10, 11, 12 for Diamonds, Hearts, Spades, Clubs and 24 for Grand. \
+    /// Null has the fixed value of Null, Null Hand, Null Ouvert or Null Ouvert Hand.
+    /// None for Ramsch and no game.
+    pub fn base_value(&self) -> Option<u16> {
+        match self.game_type {
+            GameType::Grand => Some(GRAND_BASE_VALUE),
+            GameType::Null => {
+                Some(NULL_VALUES[usize::from(self.hand) + 2 * usize::from(self.ouvert)])
+            }
+            game_type => game_type
+                .trump_suit()
+                .map(|suit| suit.suit_reiz_factor() as u16),
+        }
+    }
+}
+
+/// The result of the played game from the view of the declarer, the Skat counts for the declarer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outcome {
+    pub declarer_points: u8,
+    pub declarer_tricks: u8,
+}
+
+impl Outcome {
+    pub fn new(declarer_points: u8, declarer_tricks: u8) -> Outcome {
+        Outcome {
+            declarer_points,
+            declarer_tricks,
+        }
+    }
+
+    /// Schneider by the declarer or the opponents.
+    pub fn is_schneider(&self) -> bool {
+        self.declarer_points >= SCHNEIDER_POINTS
+            || self.declarer_points <= 120 - SCHNEIDER_POINTS
+            || self.is_schwarz()
+    }
+
+    /// All tricks taken by the declarer or the opponents.
+    pub fn is_schwarz(&self) -> bool {
+        self.declarer_tricks == 0 || self.declarer_tricks == ALL_TRICKS
+    }
+}
+
+/// The levels counted on top of the matadors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Level {
+    Game,
+    Hand,
+    Schneider,
+    SchneiderAnnounced,
+    Schwarz,
+    SchwarzAnnounced,
+    Ouvert,
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Level::Game => "game",
+            Level::Hand => "hand",
+            Level::Schneider => "Schneider",
+            Level::SchneiderAnnounced => "Schneider announced",
+            Level::Schwarz => "Schwarz",
+            Level::SchwarzAnnounced => "Schwarz announced",
+            Level::Ouvert => "ouvert",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The value of a played game with the steps leading to it, as written on a score sheet.
+/// ```
+/// use skat_engine::game::GameType;
+/// use skat_engine::matadors::Matadors;
+/// use skat_engine::cardset::CardSet;
+/// use skat_engine::value::{Contract, GameValue, Outcome};
+///
+/// let cards = CardSet::from_german(&["KB", "PB", "KA", "KZ", "KK", "PA", "PZ", "HA", "HZ", "CA"]);
+/// let matadors = Matadors::new(cards, &GameType::Clubs);
+/// let contract = Contract::new(GameType::Clubs).hand();
+/// let value = GameValue::compute(&contract, &matadors, &Outcome::new(92, 8)).unwrap();
+/// assert_eq!(value.value(), 60);
+/// assert_eq!(value.to_string(), "Clubs: with 2, game 3, hand 4, Schneider 5 × 12 = 60");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameValue {
+    contract: Contract,
+    base: u16,
+    /// None for Null.
+    matadors: Option<Matadors>,
+    levels: Vec<Level>,
+    value: u16,
+    won: bool,
+    overbid: bool,
+}
+
+impl GameValue {
+    /// Computes the value of the game with all levels reached.
+    /// Announced levels count even if they are not reached, Schwarz includes Schneider. \
+    /// None for Ramsch and no game.
+    pub fn compute(
+        contract: &Contract,
+        matadors: &Matadors,
+        outcome: &Outcome,
+    ) -> Option<GameValue> {
+        let base = contract.base_value()?;
+        if contract.game_type == GameType::Null {
+            return Some(GameValue {
+                contract: *contract,
+                base,
+                matadors: None,
+                levels: Vec::new(),
+                value: base,
+                won: outcome.declarer_tricks == 0,
+                overbid: false,
+            });
+        }
+
+        let schwarz = contract.schwarz_announced || outcome.is_schwarz();
+        let schneider = contract.schneider_announced || schwarz || outcome.is_schneider();
+        let levels: Vec<Level> = [
+            (Level::Game, true),
+            (Level::Hand, contract.hand),
+            (Level::Schneider, schneider),
+            (Level::SchneiderAnnounced, contract.schneider_announced),
+            (Level::Schwarz, schwarz),
+            (Level::SchwarzAnnounced, contract.schwarz_announced),
+            (Level::Ouvert, contract.ouvert),
+        ]
+        .into_iter()
+        .filter_map(|(level, reached)| reached.then_some(level))
+        .collect();
+
+        let won = outcome.declarer_points >= WINNING_POINTS
+            && (!contract.schneider_announced || outcome.declarer_points >= SCHNEIDER_POINTS)
+            && (!contract.schwarz_announced || outcome.declarer_tricks == ALL_TRICKS);
+        let multiplier = matadors.count() as u16 + levels.len() as u16;
+        Some(GameValue {
+            contract: *contract,
+            base,
+            matadors: Some(*matadors),
+            levels,
+            value: base * multiplier,
+            won,
+            overbid: false,
+        })
+    }
+
+    /// Checks the value against the bid. An overbid game is lost, a suit game or Grand
+    /// with the lowest multiple of the base value reaching the bid.
+    pub fn with_bid(mut self, bid: BidValue) -> GameValue {
+        if self.value < bid.value() {
+            self.overbid = true;
+            self.won = false;
+            if self.matadors.is_some() {
+                self.value = bid.value().div_ceil(self.base) * self.base;
+            }
+        }
+        self
+    }
+
+    pub fn contract(&self) -> &Contract {
+        &self.contract
+    }
+
+    /// The base value, or the fixed value of Null.
+    pub fn base(&self) -> u16 {
+        self.base
+    }
+
+    /// The matadors, None for Null.
+    pub fn matadors(&self) -> Option<Matadors> {
+        self.matadors
+    }
+
+    /// The levels counted on top of the matadors, empty for Null.
+    pub fn levels(&self) -> &[Level] {
+        &self.levels
+    }
+
+    /// The factor the base value is multiplied with, 1 for Null.
+    pub fn multiplier(&self) -> u16 {
+        self.value / self.base
+    }
+
+    /// The game value, raised to the bid for an overbid game.
+    pub fn value(&self) -> u16 {
+        self.value
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.won
+    }
+
+    pub fn is_overbid(&self) -> bool {
+        self.overbid
+    }
+
+    /// The points written for the declarer: the value if won, twice the value negative if lost.
+    pub fn score(&self) -> i32 {
+        if self.won {
+            i32::from(self.value)
+        } else {
+            -2 * i32::from(self.value)
+        }
+    }
+}
+
+/// "Clubs: with 2, game 3, hand 4 × 12 = 48", "Null Ouvert = 46",
+/// followed by ", overbid to 27" and ", lost -96" if so.
+impl Display for GameValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let contract = &self.contract;
+        match self.matadors {
+            None => {
+                let hand = if contract.hand { " Hand" } else { "" };
+                let ouvert = if contract.ouvert { " Ouvert" } else { "" };
+                write!(f, "{}{ouvert}{hand} = {}", contract.game_type, self.value)?;
+            }
+            Some(matadors) => {
+                write!(f, "{}: {matadors}", contract.game_type)?;
+                let mut multiplier = matadors.count();
+                for level in &self.levels {
+                    multiplier += 1;
+                    write!(f, ", {level} {multiplier}")?;
+                }
+                write!(f, " × {} = {}", self.base, self.base * multiplier as u16)?;
+            }
+        }
+        if self.overbid {
+            write!(f, ", overbid to {}", self.value)?;
+        }
+        if !self.won {
+            write!(f, ", lost {}", self.score())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cardset::CardSet;
+
+    fn matadors(cards: &[&str], game_type: GameType) -> Matadors {
+        Matadors::new(CardSet::from_german(cards), &game_type)
+    }
+
+    #[test]
+    fn suit_and_grand() {
+        let with_2 = matadors(&["KB", "PB", "HA"], GameType::Hearts);
+        let value = GameValue::compute(
+            &Contract::new(GameType::Hearts),
+            &with_2,
+            &Outcome::new(75, 6),
+        )
+        .unwrap();
+        assert_eq!(value.value(), 30);
+        assert!(value.is_won());
+
+        // lost Schneider by the opponents counts Schneider
+        let value = GameValue::compute(
+            &Contract::new(GameType::Hearts).hand(),
+            &with_2,
+            &Outcome::new(28, 2),
+        )
+        .unwrap();
+        assert_eq!(value.multiplier(), 5);
+        assert_eq!(value.score(), -100);
+
+        // announced levels count even if not reached, the game is lost
+        let without_1 = matadors(&["PB", "HB"], GameType::Grand);
+        let value = GameValue::compute(
+            &Contract::new(GameType::Grand).ouvert(),
+            &without_1,
+            &Outcome::new(110, 9),
+        )
+        .unwrap();
+        assert_eq!(value.multiplier(), 8);
+        assert_eq!(value.value(), 192);
+        assert!(!value.is_won());
+
+        let value = GameValue::compute(
+            &Contract::new(GameType::Spades),
+            &matadors(&["KB", "HB"], GameType::Spades),
+            &Outcome::new(120, 10),
+        )
+        .unwrap();
+        assert_eq!(
+            value.to_string(),
+            "Spades: with 1, game 2, Schneider 3, Schwarz 4 × 11 = 44"
+        );
+    }
+
+    #[test]
+    fn null_and_overbid() {
+        let none = matadors(&[], GameType::Null);
+        let value = GameValue::compute(
+            &Contract::new(GameType::Null).ouvert().hand(),
+            &none,
+            &Outcome::new(0, 0),
+        )
+        .unwrap();
+        assert_eq!(value.value(), 59);
+        assert!(value.is_won());
+        assert_eq!(value.to_string(), "Null Ouvert Hand = 59");
+
+        let with_1 = matadors(&["KB", "HB"], GameType::Diamonds);
+        let value = GameValue::compute(
+            &Contract::new(GameType::Diamonds),
+            &with_1,
+            &Outcome::new(70, 7),
+        )
+        .unwrap()
+        .with_bid(BidValue::new(20).unwrap());
+        assert!(value.is_overbid());
+        assert_eq!(value.value(), 27);
+        assert_eq!(
+            value.to_string(),
+            "Diamonds: with 1, game 2 × 9 = 18, overbid to 27, lost -54"
+        );
+
+        assert_eq!(
+            GameValue::compute(&Contract::new(GameType::Ramsch), &none, &Outcome::new(0, 0)),
+            None
+        );
+    }
+}